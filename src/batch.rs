@@ -0,0 +1,348 @@
+use crate::executor::{search, Priorities};
+use crate::heuristic::HeuristicExpr;
+use crate::map::{gen_solved_map, Map};
+use crate::parser;
+use crate::progress::NoopObserver;
+use crate::solver::{Limits, Outcome, Solver};
+use ahash::AHashMap;
+use serde::Serialize;
+use std::error::Error;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::time::Instant;
+
+// Puzzle to solve, or the reason it could not be read
+#[derive(Clone)]
+pub struct Entry {
+	pub name: String,
+	pub map: Result<Map, String>,
+}
+
+#[derive(Serialize)]
+pub struct Row {
+	pub name: String,
+	pub size: Option<u16>,
+	// solved, unsolvable, exhausted, the limit reached or an error
+	pub status: String,
+	pub moves: Option<usize>,
+	// Number of states opened
+	pub nodes: Option<usize>,
+	// Memory complexity as the search counts it: the states stored by linear,
+	// greedy and uniform, the states seen from both ends by bidirectional,
+	// and the deepest path of IDA*, which only keeps the current path
+	pub memory: Option<usize>,
+	// Wall clock time of the search, in seconds
+	pub seconds: Option<f64>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Stats {
+	pub mean: f64,
+	pub median: f64,
+	pub p95: f64,
+}
+
+// Statistics over the solved puzzles
+#[derive(Serialize)]
+pub struct Summary {
+	pub total: usize,
+	pub solved: usize,
+	pub moves: Option<Stats>,
+	pub nodes: Option<Stats>,
+	pub memory: Option<Stats>,
+	pub seconds: Option<Stats>,
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+	puzzles: &'a [Row],
+	summary: &'a Summary,
+}
+
+// The .map files of a directory, or every map of a file
+pub fn load(path: &Path) -> Result<Vec<Entry>, Box<dyn Error>> {
+	if !path.is_dir() {
+		return load_file(path);
+	}
+	let mut files: Vec<_> = fs::read_dir(path)?
+		.map(|entry| entry.map(|e| e.path()))
+		.collect::<Result<_, _>>()?;
+	files.retain(|f| f.is_file() && f.extension().is_some_and(|e| e == "map"));
+	files.sort();
+	let mut entries = Vec::new();
+	for file in files {
+		entries.extend(load_file(&file)?);
+	}
+	Ok(entries)
+}
+
+fn load_file(path: &Path) -> Result<Vec<Entry>, Box<dyn Error>> {
+	let input = fs::read_to_string(path)?;
+	let name = path.display().to_string();
+	let mut maps = parser::parse_maps(&input);
+	if maps.is_empty() {
+		maps.push(parser::parse_map(&input));
+	}
+	let single = maps.len() == 1;
+	Ok(maps
+		.into_iter()
+		.enumerate()
+		.map(|(i, map)| Entry {
+			name: match single {
+				true => name.clone(),
+				false => format!("{}:{}", name, i + 1),
+			},
+			map: map.map_err(|e| e.message()),
+		})
+		.collect())
+}
+
+// Solves every entry, the heuristic being built once per size. The status of
+// each puzzle is written to stderr unless quiet.
+pub fn solve(
+	entries: Vec<Entry>,
+	heuristic: &HeuristicExpr,
+	limits: Limits,
+	priority: Priorities,
+	quiet: bool,
+) -> Vec<Row> {
+	let mut solvers: AHashMap<u16, Result<Solver, String>> = AHashMap::new();
+	let mut rows = Vec::with_capacity(entries.len());
+	for entry in entries {
+		let mut row = Row {
+			name: entry.name,
+			size: None,
+			status: String::new(),
+			moves: None,
+			nodes: None,
+			memory: None,
+			seconds: None,
+		};
+		let map = match entry.map {
+			Ok(map) => map,
+			Err(e) => {
+				row.status = e;
+				rows.push(row);
+				continue;
+			}
+		};
+		row.size = Some(map.size);
+		let solver = solvers.entry(map.size).or_insert_with(|| {
+			let solved_map = gen_solved_map(map.size as usize);
+			heuristic
+				.build(&solved_map)
+				.map(|h| Solver::new(&solved_map, h, limits))
+				.map_err(|e| e.to_string())
+		});
+		let solver = match solver {
+			Ok(solver) => solver,
+			Err(e) => {
+				row.status = e.clone();
+				rows.push(row);
+				continue;
+			}
+		};
+		if !Solver::is_solvable(&map) {
+			row.status = "unsolvable".to_string();
+			rows.push(row);
+			continue;
+		}
+
+		let start = Instant::now();
+		let outcome = search(solver, map, priority, &mut NoopObserver);
+		row.seconds = Some(start.elapsed().as_secs_f64());
+		let (status, time, memory) = match outcome {
+			Outcome::Solved(solution) => {
+				row.moves = Some(solution.states.len() - 1);
+				("solved".to_string(), solution.time, solution.memory)
+			}
+			Outcome::LimitReached(limit, partial) => {
+				(limit.to_string(), partial.time, partial.memory)
+			}
+			Outcome::Exhausted { time, memory } => ("exhausted".to_string(), time, memory),
+		};
+		row.status = status;
+		row.nodes = Some(time);
+		row.memory = Some(memory);
+		if !quiet {
+			eprintln!("{}: {}", row.name, row.status);
+		}
+		rows.push(row);
+	}
+	rows
+}
+
+// Entries named `puzzle <i>` for the tests
+#[cfg(test)]
+pub fn test_entries(size: u16, boards: &[Vec<u16>]) -> Vec<Entry> {
+	boards
+		.iter()
+		.enumerate()
+		.map(|(i, board)| Entry {
+			name: format!("puzzle {}", i),
+			map: Ok(Map {
+				size,
+				board: board.clone(),
+			}),
+		})
+		.collect()
+}
+
+pub fn summarize(rows: &[Row]) -> Summary {
+	let solved: Vec<&Row> = rows.iter().filter(|r| r.moves.is_some()).collect();
+	let stats = |field: fn(&Row) -> Option<f64>| {
+		let values: Vec<f64> = solved.iter().filter_map(|r| field(r)).collect();
+		Stats::new(values)
+	};
+	Summary {
+		total: rows.len(),
+		solved: solved.len(),
+		moves: stats(|r| r.moves.map(|m| m as f64)),
+		nodes: stats(|r| r.nodes.map(|n| n as f64)),
+		memory: stats(|r| r.memory.map(|m| m as f64)),
+		seconds: stats(|r| r.seconds),
+	}
+}
+
+impl Stats {
+	// The 95th percentile uses the nearest rank
+	pub fn new(mut values: Vec<f64>) -> Option<Self> {
+		if values.is_empty() {
+			return None;
+		}
+		values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+		let n = values.len();
+		let median = match n % 2 {
+			0 => (values[n / 2 - 1] + values[n / 2]) / 2.0,
+			_ => values[n / 2],
+		};
+		let rank = (n * 95).div_ceil(100).max(1);
+		Some(Self {
+			mean: values.iter().sum::<f64>() / n as f64,
+			median,
+			p95: values[rank - 1],
+		})
+	}
+}
+
+pub fn write_json(out: &mut impl Write, rows: &[Row], summary: &Summary) -> serde_json::Result<()> {
+	let report = Report {
+		puzzles: rows,
+		summary,
+	};
+	serde_json::to_writer_pretty(&mut *out, &report)?;
+	writeln!(out).map_err(serde_json::Error::io)
+}
+
+// One line per puzzle, then the mean, median and p95 lines of the summary
+pub fn write_csv(out: &mut impl Write, rows: &[Row], summary: &Summary) -> std::io::Result<()> {
+	fn field<T: ToString>(value: Option<T>) -> String {
+		value.map(|v| v.to_string()).unwrap_or_default()
+	}
+
+	writeln!(out, "name,size,status,moves,nodes,memory,seconds")?;
+	for row in rows {
+		writeln!(
+			out,
+			"{},{},{},{},{},{},{}",
+			quote(&row.name),
+			field(row.size),
+			quote(&row.status),
+			field(row.moves),
+			field(row.nodes),
+			field(row.memory),
+			field(row.seconds)
+		)?;
+	}
+	for name in ["mean", "median", "p95"].iter() {
+		let value = |stats: &Stats| match *name {
+			"mean" => stats.mean,
+			"median" => stats.median,
+			_ => stats.p95,
+		};
+		writeln!(
+			out,
+			"{},,{} of {} solved,{},{},{},{}",
+			name,
+			summary.solved,
+			summary.total,
+			field(summary.moves.as_ref().map(value)),
+			field(summary.nodes.as_ref().map(value)),
+			field(summary.memory.as_ref().map(value)),
+			field(summary.seconds.as_ref().map(value))
+		)?;
+	}
+	Ok(())
+}
+
+fn quote(field: &str) -> String {
+	if field.contains(&[',', '"', '\n'][..]) {
+		format!("\"{}\"", field.replace('"', "\"\""))
+	} else {
+		field.to_string()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn stats() {
+		let stats = Stats::new(vec![4.0, 1.0, 3.0, 2.0]).unwrap();
+		assert_eq!(
+			stats,
+			Stats {
+				mean: 2.5,
+				median: 2.5,
+				p95: 4.0
+			}
+		);
+		let values: Vec<f64> = (1..=100).map(f64::from).collect();
+		let stats = Stats::new(values).unwrap();
+		assert_eq!(stats.median, 50.5);
+		assert_eq!(stats.p95, 95.0);
+		assert!(Stats::new(Vec::new()).is_none());
+	}
+
+	#[test]
+	fn solve_and_report() {
+		let maps = [
+			vec![1, 2, 3, 8, 0, 4, 7, 6, 5],
+			vec![1, 2, 3, 8, 4, 0, 7, 6, 5],
+			vec![2, 1, 3, 8, 0, 4, 7, 6, 5],
+		];
+		let mut entries = test_entries(3, &maps);
+		entries.push(Entry {
+			name: "broken, really".to_string(),
+			map: Err("Unable to parse map !".to_string()),
+		});
+		let heuristic = crate::heuristic::parse_heuristic("manhatthan").unwrap();
+		let rows = solve(
+			entries,
+			&heuristic,
+			Limits::default(),
+			Priorities::Ida,
+			true,
+		);
+		let status: Vec<&str> = rows.iter().map(|r| r.status.as_str()).collect();
+		assert_eq!(
+			status,
+			vec!["solved", "solved", "unsolvable", "Unable to parse map !"]
+		);
+		assert_eq!(rows[1].moves, Some(1));
+
+		let summary = summarize(&rows);
+		assert_eq!((summary.total, summary.solved), (4, 2));
+		assert_eq!(summary.moves.as_ref().unwrap().mean, 0.5);
+
+		let mut csv = Vec::new();
+		write_csv(&mut csv, &rows, &summary).unwrap();
+		let csv = String::from_utf8(csv).unwrap();
+		let lines: Vec<&str> = csv.lines().collect();
+		assert_eq!(lines.len(), 8);
+		assert!(lines[4].starts_with("\"broken, really\",,"));
+		assert!(lines[5].starts_with("mean,,2 of 4 solved,0.5,"));
+	}
+}
@@ -0,0 +1,255 @@
+use crate::batch::{self, Entry, Row, Summary};
+use crate::executor::Priorities;
+use crate::heuristic::{HeuristicExpr, Heuristics};
+use crate::solver::Limits;
+use clap::ArgEnum;
+use std::io::{self, Write};
+use std::time::Duration;
+
+// Limits of each search when none is given, so that a slow combination
+// cannot hold up the whole benchmark
+pub const DEFAULT_LIMITS: Limits = Limits {
+	time: Some(Duration::from_secs(10)),
+	nodes: Some(1_000_000),
+	memory: None,
+};
+
+// Results of one heuristic and search combination over the corpus
+pub struct Run {
+	// "-" when the search does not use the heuristic
+	pub heuristic: String,
+	pub search: String,
+	// Whether the solutions are guaranteed to be optimal
+	pub optimal: bool,
+	pub rows: Vec<Row>,
+	pub summary: Summary,
+}
+
+// A solution longer than the optimal one
+pub struct NonOptimal {
+	pub run: usize,
+	pub puzzle: usize,
+	pub moves: usize,
+	pub optimal: usize,
+}
+
+// Searches that do not use the heuristic are only run once, with the first
+// heuristic
+pub fn run(
+	entries: &[Entry],
+	heuristics: &[HeuristicExpr],
+	searches: &[Priorities],
+	limits: Limits,
+	quiet: bool,
+) -> Vec<Run> {
+	let mut runs = Vec::with_capacity(heuristics.len() * searches.len());
+	for (i, heuristic) in heuristics.iter().enumerate() {
+		for &search in searches {
+			if i == 0 || search.uses_heuristic() {
+				runs.push(solve(entries, heuristic, search, limits, quiet));
+			}
+		}
+	}
+	runs
+}
+
+fn solve(
+	entries: &[Entry],
+	heuristic: &HeuristicExpr,
+	search: Priorities,
+	limits: Limits,
+	quiet: bool,
+) -> Run {
+	let name = search.as_arg().unwrap_or_default();
+	let label = match search.uses_heuristic() {
+		true => heuristic.to_string(),
+		false => "-".to_string(),
+	};
+	if !quiet {
+		eprintln!("Running {} with {}", label, name);
+	}
+	let rows = batch::solve(entries.to_vec(), heuristic, limits, search, quiet);
+	Run {
+		heuristic: label,
+		search: name.to_string(),
+		optimal: search.is_optimal(heuristic.is_admissible()),
+		summary: batch::summarize(&rows),
+		rows,
+	}
+}
+
+// Optimal number of moves of each puzzle, from the runs guaranteed to be
+// optimal. When they leave a puzzle unsolved, IDA* with the pattern database is
+// run to find them.
+pub fn optimal_lengths(entries: &[Entry], runs: &[Run], limits: Limits) -> Vec<Option<usize>> {
+	let shortest =
+		|runs: &[&Run], puzzle: usize| runs.iter().filter_map(|r| r.rows[puzzle].moves).min();
+	let optimal: Vec<&Run> = runs.iter().filter(|r| r.optimal).collect();
+	let lengths: Vec<Option<usize>> = (0..entries.len()).map(|p| shortest(&optimal, p)).collect();
+	if lengths.iter().all(Option::is_some) {
+		return lengths;
+	}
+	let heuristic = HeuristicExpr::Single(Heuristics::PatternDatabase);
+	let reference = solve(entries, &heuristic, Priorities::Ida, limits, true);
+	lengths
+		.into_iter()
+		.enumerate()
+		.map(|(p, length)| length.or_else(|| shortest(&[&reference], p)))
+		.collect()
+}
+
+pub fn non_optimal(runs: &[Run], optimal: &[Option<usize>]) -> Vec<NonOptimal> {
+	let mut flagged = Vec::new();
+	for (i, run) in runs.iter().enumerate() {
+		for (puzzle, row) in run.rows.iter().enumerate() {
+			match (row.moves, optimal[puzzle]) {
+				(Some(moves), Some(optimal)) if moves > optimal => flagged.push(NonOptimal {
+					run: i,
+					puzzle,
+					moves,
+					optimal,
+				}),
+				_ => {}
+			}
+		}
+	}
+	flagged
+}
+
+// Means over the solved puzzles of each combination, marked with a * when
+// one of its solutions is not optimal
+pub fn print_table(out: &mut impl Write, runs: &[Run], flagged: &[NonOptimal]) -> io::Result<()> {
+	fn mean(stats: &Option<batch::Stats>, precision: usize) -> String {
+		match stats {
+			Some(stats) => format!("{:.*}", precision, stats.mean),
+			None => "-".to_string(),
+		}
+	}
+
+	let width = runs
+		.iter()
+		.map(|r| r.heuristic.len())
+		.max()
+		.unwrap_or(0)
+		.max("heuristic".len());
+	writeln!(
+		out,
+		"  {:width$}  {:13}  {:>7}  {:>8}  {:>12}  {:>12}  {:>10}",
+		"heuristic",
+		"search",
+		"solved",
+		"moves",
+		"nodes",
+		"memory",
+		"seconds",
+		width = width
+	)?;
+	for (i, run) in runs.iter().enumerate() {
+		let mark = match flagged.iter().any(|f| f.run == i) {
+			true => '*',
+			false => ' ',
+		};
+		writeln!(
+			out,
+			"{} {:width$}  {:13}  {:>7}  {:>8}  {:>12}  {:>12}  {:>10}",
+			mark,
+			run.heuristic,
+			run.search,
+			format!("{}/{}", run.summary.solved, run.summary.total),
+			mean(&run.summary.moves, 1),
+			mean(&run.summary.nodes, 0),
+			mean(&run.summary.memory, 0),
+			mean(&run.summary.seconds, 4),
+			width = width
+		)?;
+	}
+	if !flagged.is_empty() {
+		writeln!(out, "\nNon-optimal solutions:")?;
+		for f in flagged {
+			let run = &runs[f.run];
+			writeln!(
+				out,
+				"  {} with {}: {} in {} moves instead of {}",
+				run.heuristic, run.search, run.rows[f.puzzle].name, f.moves, f.optimal
+			)?;
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::heuristic::parse_heuristic;
+
+	fn entries() -> Vec<Entry> {
+		batch::test_entries(
+			3,
+			&[
+				vec![3, 2, 8, 4, 1, 5, 6, 7, 0],
+				vec![1, 2, 3, 8, 4, 0, 7, 6, 5],
+			],
+		)
+	}
+
+	#[test]
+	fn flag_non_optimal() {
+		// Greedy search does not look at the number of moves
+		let entries = entries();
+		let heuristics = vec![parse_heuristic("manhatthan").unwrap()];
+		let searches = [Priorities::Ida, Priorities::Greedy];
+		let runs = run(&entries, &heuristics, &searches, Limits::default(), true);
+		assert_eq!(runs.len(), 2);
+		assert_eq!(runs[0].rows[0].moves, Some(24));
+
+		let optimal = optimal_lengths(&entries, &runs, Limits::default());
+		assert_eq!(optimal, vec![Some(24), Some(1)]);
+		let flagged = non_optimal(&runs, &optimal);
+		assert!(flagged.iter().all(|f| f.run == 1 && f.optimal == 24));
+		assert_eq!(flagged.len(), 1);
+
+		let mut table = Vec::new();
+		print_table(&mut table, &runs, &flagged).unwrap();
+		let table = String::from_utf8(table).unwrap();
+		assert!(table.lines().nth(2).unwrap().starts_with("* manhatthan"));
+		assert!(table.contains("puzzle 0 in"));
+	}
+
+	#[test]
+	fn without_optimal_runs() {
+		let entries = entries();
+		let heuristics = vec![parse_heuristic("manhatthan").unwrap()];
+		let runs = run(
+			&entries,
+			&heuristics,
+			&[Priorities::Greedy],
+			Limits::default(),
+			true,
+		);
+		let optimal = optimal_lengths(&entries, &runs, Limits::default());
+		assert_eq!(optimal, vec![Some(24), Some(1)]);
+		assert_eq!(non_optimal(&runs, &optimal).len(), 1);
+	}
+
+	#[test]
+	fn heuristic_independent_searches() {
+		let heuristics = vec![
+			parse_heuristic("manhatthan").unwrap(),
+			parse_heuristic("linear-conflict").unwrap(),
+		];
+		let searches = [Priorities::Uniform, Priorities::Ida];
+		let runs = run(&entries(), &heuristics, &searches, Limits::default(), true);
+		let names: Vec<(&str, &str)> = runs
+			.iter()
+			.map(|r| (r.heuristic.as_str(), r.search.as_str()))
+			.collect();
+		assert_eq!(
+			names,
+			vec![
+				("-", "uniform"),
+				("manhatthan", "ida"),
+				("linear-conflict", "ida")
+			]
+		);
+	}
+}
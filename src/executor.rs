@@ -1,35 +1,48 @@
-use crate::heuristic::{Euclidian, Hamming, Manhatthan, HRST};
+use crate::heuristic::HeuristicExpr;
 use crate::map::{gen_solved_map, Map};
 use crate::node::{GreedyPriority, LinearPriority, UniformPriority};
-use crate::solver::{Solution, Solver};
+use crate::progress::{CursesObserver, NoopObserver, ProgressObserver, StderrObserver};
+use crate::replay::{Metadata, Replay};
+use crate::solver::{JsonReport, Limits, Outcome, Solution, Solver};
 use crate::state::State;
+use clap::ArgEnum;
 use pancurses::Window;
 use std::error::Error;
-use std::fs::File;
+use std::io;
 use std::path::PathBuf;
-use std::rc::Rc;
+use std::time::Instant;
 
 pub struct Executor {
 	map: Map,
+	// Seed of the map, if it was generated
+	seed: Option<u64>,
 	solver: Solver,
-	window: Window,
+	heuristic: String,
+	observer: Observers,
+	// None when running without the terminal interface
+	window: Option<Window>,
 }
 
 impl Executor {
-	pub fn new(map: Map, heuristic: Heuristics, window: Window) -> Self {
+	pub fn new(
+		map: Map,
+		seed: Option<u64>,
+		heuristic: &HeuristicExpr,
+		limits: Limits,
+		observer: Observers,
+		window: Option<Window>,
+	) -> Result<Self, Box<dyn Error>> {
 		let solved_map = gen_solved_map(map.size as usize);
-		let heuristic = match heuristic {
-			Heuristics::Manhatthan => HRST::Manhatthan(Manhatthan::new(&solved_map, map.size)),
-			Heuristics::Hamming => HRST::Hamming(Hamming::new(&solved_map, map.size)),
-			Heuristics::Euclidian => HRST::Euclidian(Euclidian::new(&solved_map, map.size)),
-		};
-		let solver = Solver::new(&solved_map, heuristic);
+		let solver = Solver::new(&solved_map, heuristic.build(&solved_map)?, limits);
 
-		Executor {
+		Ok(Executor {
 			solver,
 			map,
+			seed,
+			heuristic: heuristic.to_string(),
+			observer,
 			window,
-		}
+		})
 	}
 
 	pub fn run(
@@ -37,50 +50,167 @@ impl Executor {
 		priority: Priorities,
 		replay: Option<PathBuf>,
 		skip: bool,
+		format: Formats,
 	) -> Result<(), Box<dyn Error>> {
-		if self.solver.is_solvable(&self.map) {
-			let solution = Solution::<State>::from(self.solve(priority));
-			if !skip {
-				solution.print(&self.window);
+		let json = format == Formats::Json;
+		let size = self.map.size;
+		let mut metadata = Metadata {
+			heuristic: Some(self.heuristic.clone()),
+			search: priority.as_arg().map(String::from),
+			duration: None,
+			seed: self.seed,
+		};
+		if !Solver::is_solvable(&self.map) {
+			if json {
+				JsonReport::new("unsolvable", size, &metadata).write(&mut io::stdout().lock())?;
 			}
-			if let Some(mut path) = replay {
-				path.set_extension("replay");
-				let file = File::create(path)?;
-				bincode::serialize_into(file, &solution)?;
+			return Err("Puzzle is not solvable !".into());
+		}
+
+		let start = Instant::now();
+		let outcome = self.solve(priority);
+		metadata.duration = Some(start.elapsed());
+		let solution = match outcome {
+			Outcome::Solved(solution) => Solution::<State>::from(solution),
+			Outcome::LimitReached(limit, partial) => {
+				if json {
+					JsonReport {
+						limit: Some(limit),
+						time: Some(partial.time),
+						memory: Some(partial.memory),
+						..JsonReport::new("limit_reached", size, &metadata)
+					}
+					.write(&mut io::stdout().lock())?;
+				}
+				return Err(format!(
+					"{} reached after {} iterations with {} states stored, closest state found in {} moves:\n{:size$}",
+					limit,
+					partial.time,
+					partial.memory,
+					partial.states.len() - 1,
+					partial.states.last().unwrap(),
+					size = partial.size as usize
+				)
+				.into());
+			}
+			Outcome::Exhausted { time, memory } => {
+				if json {
+					JsonReport {
+						time: Some(time),
+						memory: Some(memory),
+						..JsonReport::new("exhausted", size, &metadata)
+					}
+					.write(&mut io::stdout().lock())?;
+				}
+				return Err(format!(
+					"Search space exhausted after {} iterations with {} states stored, without reaching the goal",
+					time, memory
+				)
+				.into());
 			}
-			Ok(())
-		} else {
-			println!("Puzzle is not solvable !");
-			Ok(())
+		};
+		match (&self.window, format) {
+			(_, Formats::Json) => solution.print_json(&mut io::stdout().lock(), &metadata)?,
+			(Some(window), _) if !skip => solution.print(window),
+			(Some(_), _) => {}
+			(None, _) => solution.print_text(&mut io::stdout().lock(), !skip)?,
 		}
+		if let Some(path) = replay {
+			Replay::new(solution, metadata).save(&path)?;
+		}
+		Ok(())
+	}
+
+	pub fn is_optimal(&self, priority: Priorities) -> bool {
+		priority.is_optimal(self.solver.is_admissible())
 	}
 
-	fn solve(&self, priority: Priorities) -> Solution<Rc<State>> {
-		let solution = match priority {
-			Priorities::Linear => self
-				.solver
-				.solve::<LinearPriority>(self.map.clone(), &self.window),
-			Priorities::Uniform => self
-				.solver
-				.solve::<UniformPriority>(self.map.clone(), &self.window),
-			Priorities::Greedy => self
-				.solver
-				.solve::<GreedyPriority>(self.map.clone(), &self.window),
+	fn solve(&self, priority: Priorities) -> Outcome {
+		let mut observer: Box<dyn ProgressObserver> = match self.observer {
+			Observers::Curses => match &self.window {
+				Some(window) => Box::new(CursesObserver::new(window)),
+				None => Box::new(StderrObserver),
+			},
+			Observers::Stderr => Box::new(StderrObserver),
+			Observers::None => Box::new(NoopObserver),
 		};
-		solution
+		search(&self.solver, self.map.clone(), priority, observer.as_mut())
+	}
+}
+
+// Solves with the search function of the given priority
+pub fn search(
+	solver: &Solver,
+	map: Map,
+	priority: Priorities,
+	observer: &mut dyn ProgressObserver,
+) -> Outcome {
+	match priority {
+		Priorities::Linear => solver.solve::<LinearPriority>(map, observer),
+		Priorities::Uniform => solver.solve::<UniformPriority>(map, observer),
+		Priorities::Greedy => solver.solve::<GreedyPriority>(map, observer),
+		Priorities::Ida => solver.solve_ida(map, observer),
+		Priorities::Bidirectional => solver.solve_bidirectional(map, observer),
 	}
 }
 
-#[derive(clap::ArgEnum)]
+#[derive(clap::ArgEnum, Clone, Copy)]
 pub enum Priorities {
 	Linear,
 	Greedy,
 	Uniform,
+	Ida,
+	Bidirectional,
 }
 
-#[derive(clap::ArgEnum)]
-pub enum Heuristics {
-	Manhatthan,
-	Hamming,
-	Euclidian,
+impl Priorities {
+	// Whether solutions are guaranteed to be optimal. Linear tests for the goal
+	// when generating states, so it can miss a shorter path even with an
+	// admissible heuristic, and greedy does not look at the moves at all.
+	pub fn is_optimal(self, admissible: bool) -> bool {
+		match self {
+			Priorities::Ida => admissible,
+			Priorities::Uniform | Priorities::Bidirectional => true,
+			Priorities::Linear | Priorities::Greedy => false,
+		}
+	}
+
+	// Uniform cost and bidirectional searches give the same results whatever
+	// the heuristic
+	pub fn uses_heuristic(self) -> bool {
+		!matches!(self, Priorities::Uniform | Priorities::Bidirectional)
+	}
+}
+
+#[derive(clap::ArgEnum, Clone, Copy, PartialEq)]
+pub enum Formats {
+	Text,
+	Json,
+}
+
+#[derive(clap::ArgEnum, Clone, Copy, PartialEq)]
+pub enum Reports {
+	Csv,
+	Json,
+}
+
+#[derive(clap::ArgEnum, Clone, Copy)]
+pub enum Observers {
+	Curses,
+	Stderr,
+	None,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn optimal_searches() {
+		assert!(Priorities::Ida.is_optimal(true));
+		assert!(!Priorities::Ida.is_optimal(false));
+		assert!(Priorities::Uniform.is_optimal(false));
+		assert!(!Priorities::Linear.is_optimal(true));
+		assert!(!Priorities::Greedy.is_optimal(true));
+	}
 }
@@ -0,0 +1,258 @@
+use crate::map::{gen_solved_map, Map};
+use crate::progress::NoopObserver;
+use crate::solver::{Limits, Outcome, Solution, Solver};
+use crate::state::{Move, State};
+use pancurses::{curs_set, noecho, Input, Window, A_REVERSE};
+use std::time::Duration;
+
+const HELP: &str = "arrows: move the blank, h: hint, u: undo, q: quit";
+
+// Limits of the hint searches when none is given, so that a hard puzzle
+// cannot freeze the game
+pub const HINT_LIMITS: Limits = Limits {
+	time: Some(Duration::from_secs(5)),
+	nodes: Some(5_000_000),
+	memory: None,
+};
+
+// Puzzle solved by hand, every state played is kept for undo and replay
+pub struct Game {
+	size: u16,
+	goal: State,
+	history: Vec<State>,
+	hint: Option<Move>,
+}
+
+impl Game {
+	pub fn new(map: Map) -> Self {
+		let size = map.size;
+		Self {
+			size,
+			goal: State::from(gen_solved_map(size as usize)),
+			history: vec![State::from(map)],
+			hint: None,
+		}
+	}
+
+	pub fn current(&self) -> &State {
+		self.history.last().unwrap()
+	}
+
+	pub fn moves(&self) -> usize {
+		self.history.len() - 1
+	}
+
+	pub fn is_solved(&self) -> bool {
+		*self.current() == self.goal
+	}
+
+	// Returns false when the blank cannot move that way
+	pub fn play(&mut self, m: Move) -> bool {
+		match self.current().apply(m, self.size) {
+			Some(state) => {
+				self.history.push(state);
+				self.hint = None;
+				true
+			}
+			None => false,
+		}
+	}
+
+	pub fn undo(&mut self) -> bool {
+		if self.history.len() > 1 {
+			self.history.pop();
+			self.hint = None;
+			true
+		} else {
+			false
+		}
+	}
+
+	// Next move of a solution from the current state, optimal when the
+	// heuristic is admissible
+	pub fn hint(&mut self, solver: &Solver) -> Result<Move, String> {
+		let map = Map {
+			size: self.size,
+			board: self.current().board.clone(),
+		};
+		let solution = match solver.solve_ida(map, &mut NoopObserver) {
+			Outcome::Solved(solution) => solution,
+			Outcome::LimitReached(limit, _) => {
+				return Err(format!(
+					"No hint, {} reached",
+					limit.to_string().to_lowercase()
+				))
+			}
+			Outcome::Exhausted { .. } => return Err("Puzzle is not solvable !".to_string()),
+		};
+		let m = match solution.states.get(1) {
+			Some(next) => self.current().move_to(next),
+			None => None,
+		}
+		.ok_or("Puzzle is already solved")?;
+		self.hint = Some(m);
+		Ok(m)
+	}
+
+	// The session as a solution, to be saved as a replay
+	pub fn solution(&self) -> Solution<State> {
+		Solution {
+			states: self.history.clone(),
+			size: self.size,
+			time: 0,
+			memory: 0,
+		}
+	}
+
+	fn draw_board(&self, window: &Window) {
+		let state = self.current();
+		let width = (self.size * self.size - 1).to_string().len();
+		// Tile the blank would swap with on the hinted move
+		let hinted = self
+			.hint
+			.and_then(|m| state.apply(m, self.size))
+			.and_then(|next| {
+				let blank = state.board.iter().position(|&tile| tile == 0)?;
+				Some(next.board[blank])
+			});
+		for line in state.board.chunks_exact(self.size as usize) {
+			for (i, &tile) in line.iter().enumerate() {
+				if i > 0 {
+					window.printw(" ");
+				}
+				let text = match tile {
+					0 => " ".repeat(width),
+					tile => format!("{:width$}", tile, width = width),
+				};
+				if Some(tile) == hinted {
+					window.attron(A_REVERSE);
+					window.printw(text);
+					window.attroff(A_REVERSE);
+				} else {
+					window.printw(text);
+				}
+			}
+			window.printw("\n");
+		}
+	}
+
+	// Plays until the puzzle is solved or the player quits
+	pub fn run(&mut self, window: &Window, solver: &Solver) {
+		noecho();
+		curs_set(0);
+		window.keypad(true);
+		let mut message = String::new();
+		loop {
+			window.clear();
+			window.printw(format!("Moves: {}\n\n", self.moves()));
+			self.draw_board(window);
+			window.printw(format!("\n{}\n{}", message, HELP));
+			window.refresh();
+			if self.is_solved() {
+				break;
+			}
+			message.clear();
+			let m = match window.getch() {
+				Some(Input::KeyUp) => Move::Up,
+				Some(Input::KeyDown) => Move::Down,
+				Some(Input::KeyLeft) => Move::Left,
+				Some(Input::KeyRight) => Move::Right,
+				Some(Input::Character('h')) => {
+					window.mvprintw(window.get_max_y() - 1, 0, "Searching for a hint...");
+					window.refresh();
+					match self.hint(solver) {
+						Ok(m) => message = format!("Hint: move the blank {:?}", m),
+						Err(e) => message = e,
+					}
+					continue;
+				}
+				Some(Input::Character('u')) => {
+					if !self.undo() {
+						message = "Nothing to undo".to_string();
+					}
+					continue;
+				}
+				Some(Input::Character('q')) | Some(Input::Character('\x1b')) => break,
+				_ => continue,
+			};
+			if !self.play(m) {
+				message = "The blank cannot move there".to_string();
+			}
+		}
+		if self.is_solved() {
+			window.mvprintw(
+				window.get_max_y() - 1,
+				0,
+				format!(
+					"Solved in {} moves ! Press any key to continue...",
+					self.moves()
+				),
+			);
+			window.getch();
+		}
+		curs_set(1);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::heuristic::{Manhatthan, HRST};
+
+	fn game() -> Game {
+		// Two moves away from the goal
+		Game::new(Map {
+			size: 3,
+			board: vec![1, 2, 3, 8, 6, 4, 7, 5, 0],
+		})
+	}
+
+	#[test]
+	fn play_and_undo() {
+		let mut game = game();
+		assert!(!game.play(Move::Down));
+		assert!(game.play(Move::Up));
+		assert_eq!(game.moves(), 1);
+		assert!(game.undo());
+		assert!(!game.undo());
+		assert!(game.play(Move::Left));
+		assert!(!game.is_solved());
+		assert!(game.play(Move::Up));
+		assert!(game.is_solved());
+
+		let solution = game.solution();
+		assert_eq!(solution.moves(), Ok(vec![Move::Left, Move::Up]));
+	}
+
+	#[test]
+	fn hint() {
+		let solved_map = gen_solved_map(3);
+		let heuristic = HRST::Manhatthan(Manhatthan::new(&solved_map, 3));
+		let solver = Solver::new(&solved_map, heuristic, Limits::default());
+		let mut game = game();
+		assert_eq!(game.hint(&solver), Ok(Move::Left));
+		game.play(Move::Left);
+		assert_eq!(game.hint(&solver), Ok(Move::Up));
+		game.play(Move::Up);
+		assert!(game.hint(&solver).is_err());
+	}
+
+	#[test]
+	fn hint_limit() {
+		let solved_map = gen_solved_map(3);
+		let heuristic = HRST::Manhatthan(Manhatthan::new(&solved_map, 3));
+		let limits = Limits {
+			nodes: Some(1),
+			..HINT_LIMITS
+		};
+		let solver = Solver::new(&solved_map, heuristic, limits);
+		let mut game = Game::new(Map {
+			size: 3,
+			board: vec![3, 2, 8, 4, 1, 5, 6, 7, 0],
+		});
+		assert_eq!(
+			game.hint(&solver),
+			Err("No hint, node limit reached".to_string())
+		);
+	}
+}
@@ -1,44 +1,285 @@
-use crate::map::Map;
+use crate::map::{gen_solved_map, Map};
+use crate::progress::NoopObserver;
+use crate::solver::{Limits, Outcome, Solver};
+use crate::state::State;
 extern crate rand;
 use self::rand::thread_rng;
 use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::convert::TryFrom;
+use std::ops::RangeInclusive;
 
-// generates a random map
+const MAX_ATTEMPTS: usize = 1000;
+// Searches stopped by the node limit before giving up, the puzzles being too
+// long to verify
+const MAX_LIMITED: usize = 3;
+// Nodes are counted rather than time, so that a seed always gives the same
+// puzzle whatever the machine
+const ATTEMPT_NODES: usize = 50_000_000;
+
+// Which puzzles the generator may return
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Solvability {
+	Any,
+	Solvable,
+	Unsolvable,
+}
+
+// generates a random map, the same seed always gives the same map
 pub struct Generator {
 	size: usize,
 	board_size: usize,
+	seed: u64,
 }
 
 impl Generator {
-	pub fn new(size: usize) -> Self {
+	// A random seed is drawn when none is given, so that it can be reported
+	pub fn new(size: usize, seed: Option<u64>) -> Self {
 		Self {
 			size,
 			board_size: size.pow(2),
+			seed: seed.unwrap_or_else(|| thread_rng().gen()),
 		}
 	}
 
-	pub fn generate(&self) -> Map {
+	pub fn seed(&self) -> u64 {
+		self.seed
+	}
+
+	// Uniformly shuffled board
+	pub fn generate(&self, solvability: Solvability) -> Map {
 		let mut board: Vec<u16> = (0..self.board_size as u16).collect();
 
-		board.shuffle(&mut thread_rng());
+		board.shuffle(&mut ChaCha8Rng::seed_from_u64(self.seed));
+
+		Self::fix_parity(
+			Map {
+				size: self.size as u16,
+				board,
+			},
+			solvability,
+		)
+	}
+
+	// Random walk of the blank from the goal, easier puzzles for fewer moves
+	pub fn generate_walk(&self, moves: usize, solvability: Solvability) -> Map {
+		let size = self.size as u16;
+		let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+		let mut state = State::from(gen_solved_map(self.size));
+		for _ in 0..moves {
+			let children: Vec<State> = IntoIterator::into_iter(state.gen_children(size))
+				.flatten()
+				.collect();
+			state = children.choose(&mut rng).unwrap().clone();
+		}
+
+		Self::fix_parity(
+			Map {
+				size,
+				board: state.board,
+			},
+			solvability,
+		)
+	}
+
+	// Limits of each search of generate_length
+	pub fn attempt_limits() -> Limits {
+		Limits {
+			nodes: Some(ATTEMPT_NODES),
+			..Limits::default()
+		}
+	}
+
+	// Longest optimal solution of any puzzle, when it is known for the snail
+	// goal. 3x3 was found by a breadth first search of every solvable puzzle.
+	pub fn longest_solution(size: usize) -> Option<u16> {
+		match size {
+			3 => Some(30),
+			_ => None,
+		}
+	}
+
+	// Fails when no puzzle can have an optimal solution in `moves`
+	pub fn check_length(size: usize, moves: &RangeInclusive<u16>) -> Result<(), String> {
+		match Self::longest_solution(size) {
+			Some(longest) if *moves.start() > longest => Err(format!(
+				"No {}x{} puzzle needs {}, the longest optimal solution is {} moves",
+				size,
+				size,
+				describe(moves),
+				longest
+			)),
+			_ => Ok(()),
+		}
+	}
+
+	// Random walks until the optimal solution found by IDA* has a length in
+	// `moves`, the walk being lengthened while the puzzles are too easy.
+	// The optimal length has the parity of the walk length. The solver should
+	// use attempt_limits, so that a search cannot run forever.
+	pub fn generate_length(
+		&self,
+		moves: RangeInclusive<u16>,
+		solver: &Solver,
+	) -> Result<(Map, u16), String> {
+		Self::check_length(self.size, &moves)?;
+		let (min, max) = (*moves.start() as usize, *moves.end() as usize);
+		let wanted = describe(&moves);
+
+		let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+		let mut walk = max;
+		let mut limited = 0;
+		for _ in 0..MAX_ATTEMPTS {
+			let g = Generator::new(self.size, Some(rng.gen()));
+			let map = g.generate_walk(walk, Solvability::Solvable);
+			let length = match solver.solve_ida(map.clone(), &mut NoopObserver) {
+				Outcome::Solved(solution) => solution.states.len() - 1,
+				_ => {
+					limited += 1;
+					if limited == MAX_LIMITED {
+						return Err(format!(
+							"Puzzles of {} take too long to verify on a {}x{} board",
+							wanted, self.size, self.size
+						));
+					}
+					walk = walk.saturating_sub(2).max(2);
+					continue;
+				}
+			};
+			if let Ok(length) = u16::try_from(length) {
+				if moves.contains(&length) {
+					return Ok((map, length));
+				}
+			}
+			if length < min {
+				walk += (min - length).div_ceil(2) * 2;
+			} else if walk > 2 {
+				walk -= 2;
+			}
+		}
+		Err(format!(
+			"No puzzle found with an optimal solution of {} after {} attempts",
+			wanted, MAX_ATTEMPTS
+		))
+	}
 
-		Map {
-			size: self.size as u16,
-			board,
+	// Swapping two tiles switches between solvable and unsolvable
+	fn fix_parity(mut map: Map, solvability: Solvability) -> Map {
+		let wanted = match solvability {
+			Solvability::Any => return map,
+			Solvability::Solvable => true,
+			Solvability::Unsolvable => false,
+		};
+		if Solver::is_solvable(&map) != wanted {
+			let mut tiles = (0..map.board.len()).filter(|&i| map.board[i] != 0);
+			let (a, b) = (tiles.next().unwrap(), tiles.next().unwrap());
+			map.board.swap(a, b);
 		}
+		map
+	}
+}
+
+// Range of moves as written in messages
+fn describe(moves: &RangeInclusive<u16>) -> String {
+	match moves.start() == moves.end() {
+		true => format!("{} moves", moves.start()),
+		false => format!("{} to {} moves", moves.start(), moves.end()),
 	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::heuristic::{Manhatthan, HRST};
+	use crate::solver::Limits;
 
 	#[test]
 	fn random_board() {
-		let g = Generator::new(3);
+		let g = Generator::new(3, None);
 
-		let map = g.generate();
+		let map = g.generate(Solvability::Any);
 
 		println!("{:?}", map);
 	}
+
+	#[test]
+	fn seeded_board() {
+		let map = Generator::new(4, Some(42)).generate(Solvability::Any);
+		let same = Generator::new(4, Some(42)).generate(Solvability::Any);
+		let other = Generator::new(4, Some(43)).generate(Solvability::Any);
+		assert_eq!(map.board, same.board);
+		assert_ne!(map.board, other.board);
+		assert_eq!(Generator::new(4, Some(42)).seed(), 42);
+		// ChaCha8 streams are stable, this board must not change between builds
+		assert_eq!(
+			map.board,
+			vec![0, 3, 9, 7, 12, 11, 13, 15, 1, 6, 8, 4, 5, 10, 14, 2]
+		);
+	}
+
+	#[test]
+	fn solvability() {
+		for seed in 0..20 {
+			for &size in [3, 4, 5].iter() {
+				let g = Generator::new(size, Some(seed));
+				assert!(Solver::is_solvable(&g.generate(Solvability::Solvable)));
+				assert!(!Solver::is_solvable(&g.generate(Solvability::Unsolvable)));
+				assert!(Solver::is_solvable(
+					&g.generate_walk(50, Solvability::Solvable)
+				));
+				assert!(!Solver::is_solvable(
+					&g.generate_walk(50, Solvability::Unsolvable)
+				));
+			}
+		}
+	}
+
+	#[test]
+	fn length() {
+		let solved_map = gen_solved_map(3);
+		let heuristic = HRST::Manhatthan(Manhatthan::new(&solved_map, 3));
+		let solver = Solver::new(&solved_map, heuristic, Limits::default());
+		let g = Generator::new(3, Some(3));
+		for moves in [12..=12, 15..=18, 21..=21].iter() {
+			let (map, length) = g.generate_length(moves.clone(), &solver).unwrap();
+			assert!(moves.contains(&length));
+			match solver.solve_ida(map, &mut NoopObserver) {
+				Outcome::Solved(solution) => assert_eq!(solution.states.len() - 1, length as usize),
+				_ => panic!("expected a solution"),
+			}
+		}
+	}
+
+	#[test]
+	fn unreachable_length() {
+		let solved_map = gen_solved_map(3);
+		let heuristic = HRST::Manhatthan(Manhatthan::new(&solved_map, 3));
+		let limits = Limits {
+			nodes: Some(1000),
+			..Limits::default()
+		};
+		let solver = Solver::new(&solved_map, heuristic, limits);
+		let g = Generator::new(3, Some(3));
+		let e = g.generate_length(31..=40, &solver).unwrap_err();
+		assert!(e.contains("the longest optimal solution is 30 moves"));
+		assert!(Generator::check_length(3, &(30..=30)).is_ok());
+		assert!(Generator::check_length(4, &(81..=81)).is_ok());
+		let e = g.generate_length(30..=30, &solver).unwrap_err();
+		assert!(e.contains("take too long to verify"));
+	}
+
+	#[test]
+	fn walk() {
+		let map = Generator::new(3, Some(1)).generate_walk(0, Solvability::Any);
+		assert_eq!(map.board, gen_solved_map(3).board);
+		let map = Generator::new(3, Some(1)).generate_walk(1, Solvability::Any);
+		let moved = map
+			.board
+			.iter()
+			.zip(gen_solved_map(3).board.iter())
+			.filter(|(a, b)| a != b)
+			.count();
+		assert_eq!(moved, 2);
+	}
 }
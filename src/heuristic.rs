@@ -1,10 +1,26 @@
 use crate::map::Map;
+use crate::pdb::PatternDatabase;
 use crate::state::Point;
 use crate::state::State;
+use ahash::AHashMap;
+use clap::ArgEnum;
+use nom::branch::alt;
+use nom::bytes::complete::{tag, take_while1};
+use nom::character::complete::char;
+use nom::combinator::{all_consuming, map, map_res};
+use nom::multi::separated_list1;
+use nom::number::complete::float;
+use nom::sequence::{delimited, separated_pair};
+use nom::IResult;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
 
 pub trait Heuristic {
 	// fn new(solved_map: &Map, size: u16) -> Self;
 	fn compute_score(&self, state: &State) -> u16;
+	// false when the score may overestimate, and the solution may not be optimal
+	fn is_admissible(&self) -> bool;
 }
 
 pub struct Manhatthan {
@@ -28,9 +44,11 @@ impl Manhatthan {
 	fn compute_score(&self, state: &State) -> u16 {
 		let mut score: u16 = 0;
 		for (i, item) in state.board.iter().enumerate() {
-			let point = Point::from_1d(i as u16, self.size);
-			let dist = Self::dist(&point, &self.solved_table[*item as usize]);
-			score += dist;
+			if *item != 0 {
+				let point = Point::from_1d(i as u16, self.size);
+				let dist = Self::dist(&point, &self.solved_table[*item as usize]);
+				score += dist;
+			}
 		}
 		score
 	}
@@ -68,11 +86,11 @@ impl Hamming {
 	fn compute_score(&self, state: &State) -> u16 {
 		let mut score: u16 = 0;
 		for (i, item) in state.board.iter().enumerate() {
-			if self.solved_table[*item as usize] == i as u16 {
+			if *item != 0 && self.solved_table[*item as usize] != i as u16 {
 				score += 1;
 			}
 		}
-		state.board.len() as u16 - score
+		score
 	}
 }
 
@@ -97,32 +115,302 @@ impl Euclidian {
 	fn compute_score(&self, state: &State) -> u16 {
 		let mut score: u16 = 0;
 		for (i, item) in state.board.iter().enumerate() {
-			let point = Point::from_1d(i as u16, self.size);
-			let dist = Self::dist(&point, &self.solved_table[*item as usize]);
-			score += dist;
+			if *item != 0 {
+				let point = Point::from_1d(i as u16, self.size);
+				let dist = Self::dist(&point, &self.solved_table[*item as usize]);
+				score += dist;
+			}
 		}
 		score
 	}
 
+	// Straight line distance rounded up, which never exceeds the manhattan
+	// distance since the latter is an integer greater or equal to it
 	pub fn dist(p1: &Point, p2: &Point) -> u16 {
 		let x_dist = match p1.x > p2.x {
-			true => p1.x.pow(2) - p2.x.pow(2),
-			false => p2.x.pow(2) - p1.x.pow(2),
-		};
+			true => p1.x - p2.x,
+			false => p2.x - p1.x,
+		} as u32;
 
-		let y_dist = match p1.y.pow(2) > p2.y.pow(2) {
-			true => p1.y.pow(2) - p2.y.pow(2),
-			false => p2.y.pow(2) - p1.y.pow(2),
-		};
+		let y_dist = match p1.y > p2.y {
+			true => p1.y - p2.y,
+			false => p2.y - p1.y,
+		} as u32;
 
-		x_dist + y_dist
+		let square = x_dist.pow(2) + y_dist.pow(2);
+		let mut root = (square as f64).sqrt() as u32;
+		while root * root < square {
+			root += 1;
+		}
+		root as u16
+	}
+}
+
+// Manhattan distance plus two moves for every tile that has to leave its line
+// to let another one pass. Goal positions come from the solved table, so the
+// snail goal is handled the same way as a row-major one.
+pub struct LinearConflict {
+	solved_table: Vec<Point>,
+	size: u16,
+}
+
+impl LinearConflict {
+	pub fn new(solved_map: &Map, size: u16) -> Self {
+		let mut solved_table: Vec<Point> =
+			vec![Point::from_1d(0, solved_map.size); solved_map.board.len()];
+
+		for (i, item) in solved_map.board.iter().enumerate() {
+			let p = Point::from_1d(i as u16, solved_map.size);
+			solved_table[*item as usize] = p;
+		}
+
+		Self { solved_table, size }
+	}
+
+	fn compute_score(&self, state: &State) -> u16 {
+		let size = self.size as usize;
+		let mut score: u16 = 0;
+		for (i, item) in state.board.iter().enumerate() {
+			if *item != 0 {
+				let point = Point::from_1d(i as u16, self.size);
+				score += Manhatthan::dist(&point, &self.solved_table[*item as usize]);
+			}
+		}
+
+		let mut line: Vec<u16> = Vec::with_capacity(size);
+		for row in 0..size {
+			line.clear();
+			for item in &state.board[row * size..(row + 1) * size] {
+				let goal = &self.solved_table[*item as usize];
+				if *item != 0 && goal.x as usize == row {
+					line.push(goal.y);
+				}
+			}
+			score += 2 * Self::line_conflicts(&line);
+		}
+		for col in 0..size {
+			line.clear();
+			for item in state.board.iter().skip(col).step_by(size) {
+				let goal = &self.solved_table[*item as usize];
+				if *item != 0 && goal.y as usize == col {
+					line.push(goal.x);
+				}
+			}
+			score += 2 * Self::line_conflicts(&line);
+		}
+		score
+	}
+
+	// Minimum number of tiles to remove from the line so that the remaining
+	// ones are already in goal order (length minus longest increasing subsequence)
+	fn line_conflicts(goals: &[u16]) -> u16 {
+		let mut tails: Vec<u16> = Vec::with_capacity(goals.len());
+		for goal in goals {
+			match tails.binary_search(goal) {
+				Ok(_) => {}
+				Err(i) if i == tails.len() => tails.push(*goal),
+				Err(i) => tails[i] = *goal,
+			}
+		}
+		(goals.len() - tails.len()) as u16
+	}
+}
+
+// Sum of the row and column walking distances. A walking table is keyed by
+// the matrix counting, for each line of the board, how many tiles belong to
+// each goal line, plus the line of the blank. Tiles are grouped by the line of
+// their goal position, so the snail goal needs no special treatment.
+pub struct WalkingDistance {
+	solved_table: Vec<Point>,
+	size: u16,
+	rows: AHashMap<u64, u8>,
+	cols: AHashMap<u64, u8>,
+}
+
+impl WalkingDistance {
+	pub fn new(solved_map: &Map, size: u16) -> Result<Self, &'static str> {
+		if size > 4 {
+			return Err("Walking distance is only available up to 4x4");
+		}
+		let mut solved_table: Vec<Point> =
+			vec![Point::from_1d(0, solved_map.size); solved_map.board.len()];
+
+		for (i, item) in solved_map.board.iter().enumerate() {
+			let p = Point::from_1d(i as u16, solved_map.size);
+			solved_table[*item as usize] = p;
+		}
+
+		let rows = Self::build_table(&solved_table, size, |p| p.x);
+		let cols = Self::build_table(&solved_table, size, |p| p.y);
+
+		Ok(Self {
+			solved_table,
+			size,
+			rows,
+			cols,
+		})
+	}
+
+	// Breadth first search from the goal counts: moving the blank to an adjacent
+	// line brings one tile of any goal line from there into the blank's line
+	fn build_table(
+		solved_table: &[Point],
+		size: u16,
+		line: fn(&Point) -> u16,
+	) -> AHashMap<u64, u8> {
+		let size = size as usize;
+		let mut counts = vec![0u8; size * size];
+		for point in solved_table[1..].iter() {
+			let l = line(point) as usize;
+			counts[l * size + l] += 1;
+		}
+		let start = Self::encode(&counts, line(&solved_table[0]) as usize);
+
+		let mut table = AHashMap::new();
+		let mut queue = VecDeque::new();
+		table.insert(start, 0);
+		queue.push_back(start);
+
+		while let Some(key) = queue.pop_front() {
+			let cost = table[&key];
+			let (counts, blank) = Self::decode(key, size);
+			let neighbours = [blank.checked_sub(1), Some(blank + 1).filter(|&l| l < size)];
+			for other in neighbours.iter().flatten() {
+				for goal in 0..size {
+					if counts[other * size + goal] > 0 {
+						let mut child = counts.clone();
+						child[other * size + goal] -= 1;
+						child[blank * size + goal] += 1;
+						let child = Self::encode(&child, *other);
+						if !table.contains_key(&child) {
+							table.insert(child, cost + 1);
+							queue.push_back(child);
+						}
+					}
+				}
+			}
+		}
+		table
+	}
+
+	fn encode(counts: &[u8], blank: usize) -> u64 {
+		counts
+			.iter()
+			.fold(blank as u64, |key, count| key << 3 | *count as u64)
+	}
+
+	fn decode(mut key: u64, size: usize) -> (Vec<u8>, usize) {
+		let mut counts = vec![0u8; size * size];
+		for count in counts.iter_mut().rev() {
+			*count = (key & 7) as u8;
+			key >>= 3;
+		}
+		(counts, key as usize)
+	}
+
+	fn compute_score(&self, state: &State) -> u16 {
+		let size = self.size as usize;
+		let mut rows = vec![0u8; size * size];
+		let mut cols = vec![0u8; size * size];
+		let mut blank = Point::from_1d(0, self.size);
+
+		for (i, item) in state.board.iter().enumerate() {
+			let point = Point::from_1d(i as u16, self.size);
+			if *item == 0 {
+				blank = point;
+			} else {
+				let goal = &self.solved_table[*item as usize];
+				rows[point.x as usize * size + goal.x as usize] += 1;
+				cols[point.y as usize * size + goal.y as usize] += 1;
+			}
+		}
+
+		self.rows[&Self::encode(&rows, blank.x as usize)] as u16
+			+ self.cols[&Self::encode(&cols, blank.y as usize)] as u16
+	}
+}
+
+// Largest score of its heuristics, admissible when all of them are
+pub struct Max {
+	heuristics: Vec<HRST>,
+}
+
+impl Max {
+	pub fn new(heuristics: Vec<HRST>) -> Self {
+		Self { heuristics }
+	}
+
+	fn compute_score(&self, state: &State) -> u16 {
+		self.heuristics
+			.iter()
+			.map(|h| h.compute_score(state))
+			.max()
+			.unwrap_or(0)
+	}
+
+	fn is_admissible(&self) -> bool {
+		self.heuristics.iter().all(|h| h.is_admissible())
+	}
+}
+
+// Sum of its heuristics, only admissible if they are computed on disjoint
+// sets of tiles, which can not be checked here
+pub struct Sum {
+	heuristics: Vec<HRST>,
+}
+
+impl Sum {
+	pub fn new(heuristics: Vec<HRST>) -> Self {
+		Self { heuristics }
+	}
+
+	fn compute_score(&self, state: &State) -> u16 {
+		self.heuristics.iter().map(|h| h.compute_score(state)).sum()
+	}
+
+	fn is_admissible(&self) -> bool {
+		self.heuristics.len() == 1 && self.heuristics[0].is_admissible()
+	}
+}
+
+// Largest weight accepted, so that moves + score still fits in a u16
+pub const MAX_WEIGHT: f32 = 100.0;
+
+// Score multiplied by weight and rounded up, for weighted A*.
+// A weight above 1 trades optimality for speed.
+pub struct Weighted {
+	heuristic: Box<HRST>,
+	weight: f32,
+}
+
+impl Weighted {
+	pub fn new(heuristic: HRST, weight: f32) -> Self {
+		Self {
+			heuristic: Box::new(heuristic),
+			weight,
+		}
+	}
+
+	fn compute_score(&self, state: &State) -> u16 {
+		(self.heuristic.compute_score(state) as f32 * self.weight).ceil() as u16
+	}
+
+	fn is_admissible(&self) -> bool {
+		self.weight <= 1.0 && self.heuristic.is_admissible()
 	}
 }
 
+#[allow(clippy::upper_case_acronyms)]
 pub enum HRST {
 	Manhatthan(Manhatthan),
 	Hamming(Hamming),
 	Euclidian(Euclidian),
+	LinearConflict(LinearConflict),
+	PatternDatabase(PatternDatabase),
+	WalkingDistance(WalkingDistance),
+	Max(Max),
+	Sum(Sum),
+	Weighted(Weighted),
 }
 
 impl Heuristic for HRST {
@@ -131,6 +419,349 @@ impl Heuristic for HRST {
 			HRST::Manhatthan(s) => s.compute_score(state),
 			HRST::Hamming(s) => s.compute_score(state),
 			HRST::Euclidian(s) => s.compute_score(state),
+			HRST::LinearConflict(s) => s.compute_score(state),
+			HRST::PatternDatabase(s) => s.compute_score(state),
+			HRST::WalkingDistance(s) => s.compute_score(state),
+			HRST::Max(s) => s.compute_score(state),
+			HRST::Sum(s) => s.compute_score(state),
+			HRST::Weighted(s) => s.compute_score(state),
+		}
+	}
+
+	fn is_admissible(&self) -> bool {
+		match self {
+			HRST::Manhatthan(_)
+			| HRST::Hamming(_)
+			| HRST::Euclidian(_)
+			| HRST::LinearConflict(_)
+			| HRST::PatternDatabase(_)
+			| HRST::WalkingDistance(_) => true,
+			HRST::Max(s) => s.is_admissible(),
+			HRST::Sum(s) => s.is_admissible(),
+			HRST::Weighted(s) => s.is_admissible(),
+		}
+	}
+}
+
+#[derive(clap::ArgEnum)]
+pub enum Heuristics {
+	#[clap(alias = "manhattan")]
+	Manhatthan,
+	Hamming,
+	Euclidian,
+	LinearConflict,
+	PatternDatabase,
+	WalkingDistance,
+}
+
+// Heuristic given on the command line, either a single heuristic or a
+// combination such as `max(manhatthan,weighted(linear-conflict,1.5))`
+pub enum HeuristicExpr {
+	Single(Heuristics),
+	Max(Vec<HeuristicExpr>),
+	Sum(Vec<HeuristicExpr>),
+	Weighted(Box<HeuristicExpr>, f32),
+}
+
+impl Display for HeuristicExpr {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let write_list = |f: &mut Formatter<'_>, name: &str, exprs: &Vec<HeuristicExpr>| {
+			write!(f, "{}(", name)?;
+			for (i, expr) in exprs.iter().enumerate() {
+				if i > 0 {
+					f.write_str(",")?;
+				}
+				write!(f, "{}", expr)?;
+			}
+			f.write_str(")")
+		};
+
+		match self {
+			HeuristicExpr::Single(heuristic) => f.write_str(heuristic.as_arg().unwrap_or_default()),
+			HeuristicExpr::Max(exprs) => write_list(f, "max", exprs),
+			HeuristicExpr::Sum(exprs) => write_list(f, "sum", exprs),
+			HeuristicExpr::Weighted(expr, weight) => write!(f, "weighted({},{})", expr, weight),
+		}
+	}
+}
+
+impl HeuristicExpr {
+	// Same rules as the built heuristics, without building them
+	pub fn is_admissible(&self) -> bool {
+		match self {
+			HeuristicExpr::Single(_) => true,
+			HeuristicExpr::Max(exprs) => exprs.iter().all(|e| e.is_admissible()),
+			HeuristicExpr::Sum(exprs) => exprs.len() == 1 && exprs[0].is_admissible(),
+			HeuristicExpr::Weighted(expr, weight) => *weight <= 1.0 && expr.is_admissible(),
 		}
 	}
+
+	pub fn build(&self, solved_map: &Map) -> Result<HRST, Box<dyn Error>> {
+		let size = solved_map.size;
+		let build_all = |exprs: &Vec<HeuristicExpr>| -> Result<Vec<HRST>, Box<dyn Error>> {
+			exprs.iter().map(|e| e.build(solved_map)).collect()
+		};
+
+		Ok(match self {
+			HeuristicExpr::Single(heuristic) => match heuristic {
+				Heuristics::Manhatthan => HRST::Manhatthan(Manhatthan::new(solved_map, size)),
+				Heuristics::Hamming => HRST::Hamming(Hamming::new(solved_map, size)),
+				Heuristics::Euclidian => HRST::Euclidian(Euclidian::new(solved_map, size)),
+				Heuristics::LinearConflict => {
+					HRST::LinearConflict(LinearConflict::new(solved_map, size))
+				}
+				Heuristics::PatternDatabase => {
+					HRST::PatternDatabase(PatternDatabase::new(solved_map, size))
+				}
+				Heuristics::WalkingDistance => {
+					HRST::WalkingDistance(WalkingDistance::new(solved_map, size)?)
+				}
+			},
+			HeuristicExpr::Max(exprs) => HRST::Max(Max::new(build_all(exprs)?)),
+			HeuristicExpr::Sum(exprs) => HRST::Sum(Sum::new(build_all(exprs)?)),
+			HeuristicExpr::Weighted(expr, weight) => {
+				HRST::Weighted(Weighted::new(expr.build(solved_map)?, *weight))
+			}
+		})
+	}
+}
+
+pub fn parse_heuristic(input: &str) -> Result<HeuristicExpr, String> {
+	let input: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+	let result = all_consuming(heuristic_expr)(&input);
+	match result {
+		Ok((_, expr)) => Ok(expr),
+		Err(_) => Err(format!(
+			"invalid heuristic `{}`, expected one of {} or max(..), sum(..), weighted(.., <weight>) with a weight above 0 and up to {}",
+			input,
+			Heuristics::VARIANTS.join(", "),
+			MAX_WEIGHT
+		)),
+	}
+}
+
+fn heuristic_expr(input: &str) -> IResult<&str, HeuristicExpr> {
+	let list = |input| separated_list1(char(','), heuristic_expr)(input);
+	let weight = map_res(float, |w: f32| {
+		if w > 0.0 && w <= MAX_WEIGHT {
+			Ok(w)
+		} else {
+			Err("weight out of range")
+		}
+	});
+
+	alt((
+		map(delimited(tag("max("), list, char(')')), HeuristicExpr::Max),
+		map(delimited(tag("sum("), list, char(')')), HeuristicExpr::Sum),
+		map(
+			delimited(
+				tag("weighted("),
+				separated_pair(heuristic_expr, char(','), weight),
+				char(')'),
+			),
+			|(expr, weight)| HeuristicExpr::Weighted(Box::new(expr), weight),
+		),
+		map_res(
+			take_while1(|c: char| c.is_ascii_alphanumeric() || c == '-'),
+			|name| Heuristics::from_str(name, true).map(HeuristicExpr::Single),
+		),
+	))(input)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::map::gen_solved_map;
+
+	fn state(size: u16, board: Vec<u16>) -> State {
+		State::from(Map { size, board })
+	}
+
+	#[test]
+	fn euclidian_dist() {
+		let origin = Point::from_1d(0, 4);
+		assert_eq!(Euclidian::dist(&origin, &Point::from_1d(3, 4)), 3);
+		// sqrt(2) and sqrt(18) are rounded up
+		assert_eq!(Euclidian::dist(&origin, &Point::from_1d(5, 4)), 2);
+		assert_eq!(Euclidian::dist(&origin, &Point::from_1d(15, 4)), 5);
+		assert_eq!(Euclidian::dist(&Point::from_1d(15, 4), &origin), 5);
+	}
+
+	#[test]
+	fn euclidian_below_manhattan() {
+		let solved = gen_solved_map(4);
+		let euclidian = Euclidian::new(&solved, 4);
+		let manhattan = Manhatthan::new(&solved, 4);
+		let s = state(
+			4,
+			vec![13, 11, 14, 0, 2, 1, 10, 6, 3, 9, 7, 8, 5, 12, 15, 4],
+		);
+		assert!(euclidian.compute_score(&s) <= manhattan.compute_score(&s));
+		assert_eq!(euclidian.compute_score(&State::from(solved)), 0);
+	}
+
+	#[test]
+	fn combinators() {
+		let solved = gen_solved_map(3);
+		let manhattan = || HRST::Manhatthan(Manhatthan::new(&solved, 3));
+		let hamming = || HRST::Hamming(Hamming::new(&solved, 3));
+		let s = state(3, vec![3, 2, 8, 4, 1, 5, 6, 7, 0]);
+		let m = manhattan().compute_score(&s);
+		let h = hamming().compute_score(&s);
+
+		let max = HRST::Max(Max::new(vec![manhattan(), hamming()]));
+		assert_eq!(max.compute_score(&s), m.max(h));
+		assert!(max.is_admissible());
+
+		let sum = HRST::Sum(Sum::new(vec![manhattan(), hamming()]));
+		assert_eq!(sum.compute_score(&s), m + h);
+		assert!(!sum.is_admissible());
+
+		let weighted = HRST::Weighted(Weighted::new(manhattan(), 1.5));
+		assert_eq!(weighted.compute_score(&s), (m * 3).div_ceil(2));
+		assert!(!weighted.is_admissible());
+		assert!(HRST::Weighted(Weighted::new(manhattan(), 0.5)).is_admissible());
+	}
+
+	#[test]
+	fn linear_conflict_solved() {
+		for size in 3..8 {
+			let solved = gen_solved_map(size);
+			let h = LinearConflict::new(&solved, solved.size);
+			assert_eq!(h.compute_score(&State::from(solved)), 0);
+		}
+	}
+
+	#[test]
+	fn linear_conflict_row() {
+		// 1 and 2 swapped on the first row: 2 moves of manhattan + 2 for the conflict
+		let solved = gen_solved_map(3);
+		let h = LinearConflict::new(&solved, 3);
+		assert_eq!(
+			h.compute_score(&state(3, vec![2, 1, 3, 8, 0, 4, 7, 6, 5])),
+			4
+		);
+	}
+
+	#[test]
+	fn linear_conflict_snail_column() {
+		// 3 and 4 share the last column in the snail goal, 5 is below them
+		let solved = gen_solved_map(3);
+		let h = LinearConflict::new(&solved, 3);
+		assert_eq!(
+			h.compute_score(&state(3, vec![1, 2, 4, 8, 0, 3, 7, 6, 5])),
+			4
+		);
+		// 6 and 7 are reversed on the last row, which is not in row-major order
+		assert_eq!(
+			h.compute_score(&state(3, vec![1, 2, 3, 8, 0, 4, 6, 7, 5])),
+			4
+		);
+	}
+
+	#[test]
+	fn walking_distance_solved() {
+		for size in 3..5 {
+			let solved = gen_solved_map(size);
+			let h = WalkingDistance::new(&solved, solved.size).unwrap();
+			assert_eq!(h.compute_score(&State::from(solved)), 0);
+		}
+	}
+
+	#[test]
+	fn walking_distance_one_move() {
+		let solved = gen_solved_map(4);
+		let h = WalkingDistance::new(&solved, 4).unwrap();
+		let s = state(
+			4,
+			vec![1, 2, 3, 4, 12, 13, 14, 5, 11, 15, 0, 6, 10, 9, 8, 7],
+		);
+		assert_eq!(h.compute_score(&s), 1);
+	}
+
+	#[test]
+	fn walking_distance_dominates_manhattan() {
+		let solved = gen_solved_map(4);
+		let wd = WalkingDistance::new(&solved, 4).unwrap();
+		let goal = Manhatthan::new(&solved, 4);
+		let s = state(
+			4,
+			vec![13, 11, 14, 0, 2, 1, 10, 6, 3, 9, 7, 8, 5, 12, 15, 4],
+		);
+		let manhattan: u16 = s
+			.board
+			.iter()
+			.enumerate()
+			.filter(|(_, item)| **item != 0)
+			.map(|(i, item)| {
+				Manhatthan::dist(
+					&Point::from_1d(i as u16, 4),
+					&goal.solved_table[*item as usize],
+				)
+			})
+			.sum();
+		assert!(wd.compute_score(&s) >= manhattan);
+	}
+
+	#[test]
+	fn walking_distance_too_large() {
+		assert!(WalkingDistance::new(&gen_solved_map(5), 5).is_err());
+	}
+
+	#[test]
+	fn parse_single() {
+		assert!(matches!(
+			parse_heuristic("linear-conflict"),
+			Ok(HeuristicExpr::Single(Heuristics::LinearConflict))
+		));
+		assert!(matches!(
+			parse_heuristic("manhattan"),
+			Ok(HeuristicExpr::Single(Heuristics::Manhatthan))
+		));
+		assert!(parse_heuristic("manhatan").is_err());
+	}
+
+	#[test]
+	fn parse_combination() {
+		match parse_heuristic("max(manhatthan, weighted(linear-conflict,1.5))") {
+			Ok(HeuristicExpr::Max(exprs)) => {
+				assert_eq!(exprs.len(), 2);
+				assert!(matches!(
+					&exprs[1],
+					HeuristicExpr::Weighted(e, w)
+						if *w == 1.5 && matches!(**e, HeuristicExpr::Single(Heuristics::LinearConflict))
+				));
+			}
+			_ => panic!("expected max"),
+		}
+		assert!(matches!(
+			parse_heuristic("sum(hamming,euclidian)"),
+			Ok(HeuristicExpr::Sum(_))
+		));
+		assert!(parse_heuristic("weighted(hamming,0)").is_err());
+		assert!(parse_heuristic("weighted(manhatthan,100)").is_ok());
+		assert!(parse_heuristic("weighted(manhatthan,10000)").is_err());
+		assert!(parse_heuristic("weighted(manhatthan,inf)").is_err());
+		assert!(parse_heuristic("max()").is_err());
+		assert!(parse_heuristic("max(hamming").is_err());
+	}
+
+	#[test]
+	fn expr_admissible() {
+		let admissible = |input| parse_heuristic(input).unwrap().is_admissible();
+		assert!(admissible("max(manhatthan,linear-conflict)"));
+		assert!(!admissible("sum(manhatthan,hamming)"));
+		assert!(!admissible("weighted(manhatthan,1.5)"));
+		assert!(admissible("weighted(manhatthan,0.5)"));
+	}
+
+	#[test]
+	fn display_roundtrip() {
+		let input = "max(manhatthan,sum(hamming,euclidian),weighted(linear-conflict,1.5))";
+		assert_eq!(parse_heuristic(input).unwrap().to_string(), input);
+		assert_eq!(
+			parse_heuristic("manhattan").unwrap().to_string(),
+			"manhatthan"
+		);
+	}
 }
@@ -1,72 +1,412 @@
-use crate::generator::Generator;
-use crate::map::Map;
-use crate::solver::Solution;
+use crate::game::Game;
+use crate::generator::{Generator, Solvability};
+use crate::heuristic::{HeuristicExpr, Heuristics, HRST};
+use crate::map::{gen_solved_map, Comment, Map};
+use crate::pdb::PatternDatabase;
+use crate::replay::{Metadata, Replay};
+use crate::solver::{Limits, Solution, Solver};
 use crate::state::State;
-use clap::Clap;
-use clap_num::si_number_range;
+use clap::{ArgEnum, Clap};
+use clap_num::{si_number, si_number_range};
 use executor::Executor;
-use executor::Heuristics;
+use executor::Formats;
+use executor::Observers;
 use executor::Priorities;
+use executor::Reports;
 use pancurses::{endwin, initscr};
 use std::error::Error;
-use std::fs::File;
-use std::io::Read;
+use std::io::{IsTerminal, Read, Write};
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
 use std::{fs, io};
-use std::ffi::OsStr;
-use flate2::read::GzDecoder;
 
+mod batch;
+mod bench;
 mod executor;
+mod game;
 mod generator;
 mod heuristic;
 mod map;
 mod node;
 mod parser;
+mod pdb;
+mod player;
+mod progress;
+mod replay;
 mod solver;
 mod state;
+mod verify;
 
 #[derive(Clap)]
 struct Opts {
+	#[clap(subcommand)]
+	command: Command,
+}
+
+#[derive(Clap)]
+enum Command {
+	/// Solve a puzzle
+	Solve(SolveOpts),
+	/// Solve a puzzle yourself in the terminal
+	Play(PlayOpts),
+	/// Write a random puzzle in the map format
+	Generate(GenerateOpts),
+	/// Replay a saved solution
+	Replay(ReplayOpts),
+	/// Solve every puzzle of a directory or file and report statistics
+	///
+	/// Memory is the number of states stored, except for IDA* which only keeps
+	/// its current path and reports the deepest one.
+	Batch(BatchOpts),
+	/// Compare heuristics and search functions over a set of puzzles
+	///
+	/// Unless a limit is given, each search stops after 10 seconds or 1M nodes.
+	/// Uniform and bidirectional searches do not use the heuristic and are only
+	/// run once. Solutions longer than the optimal one, found by the optimal
+	/// combinations or by IDA* with the pattern database, are marked with a *.
+	/// Memory is the number of states stored, except for IDA* which only keeps
+	/// its current path and reports the deepest one.
+	Bench(BenchOpts),
+	/// Check that a replay or moves of the blank solve a puzzle
+	Verify(VerifyOpts),
+}
+
+#[derive(Clap)]
+struct SolveOpts {
 	/// Search function to use
 	#[clap(short, long, arg_enum, default_value = "linear")]
 	search: Priorities,
-	/// Heuristic function to use
-	#[clap(short, long, arg_enum, default_value = "manhatthan")]
-	heuristic: Heuristics,
-	/// Puzzle to solve
-	map: Option<PathBuf>,
-	/// Save the solution for replay
-	#[clap(long, parse(from_os_str))]
-	save: Option<PathBuf>,
+	#[clap(flatten)]
+	map: MapOpts,
+	#[clap(flatten)]
+	solver: SolverOpts,
+	#[clap(flatten)]
+	output: OutputOpts,
+	#[clap(flatten)]
+	save: SaveOpts,
 	/// Do not print solution
 	#[clap(long)]
 	skip: bool,
-	/// Generate random map of size <GENERATE> if greater than 0
-	#[clap(short, long, parse(try_from_str=generator_size), default_value="2")]
-	generate: u16,
-	/// Replay solution
+	/// Where to report the search progress [default: curses, or none without the terminal interface]
+	#[clap(long, arg_enum)]
+	progress: Option<Observers>,
+	/// Play moves of the blank given as a string such as RRDLU on the puzzle,
+	/// instead of solving it
+	#[clap(long)]
+	moves: Option<String>,
+}
+
+#[derive(Clap)]
+struct PlayOpts {
+	#[clap(flatten)]
+	map: MapOpts,
+	/// Solver used for hints, limited to 5 seconds and 5M nodes per hint when
+	/// no limit is given
+	#[clap(flatten)]
+	solver: SolverOpts,
+	#[clap(flatten)]
+	save: SaveOpts,
+}
+
+#[derive(Clap)]
+struct GenerateOpts {
+	/// Size of the puzzle
+	#[clap(parse(try_from_str = generator_size))]
+	size: u16,
+	/// Write the puzzle to <OUTPUT> instead of the standard output
 	#[clap(short, long, parse(from_os_str))]
+	output: Option<PathBuf>,
+	#[clap(flatten)]
+	generator: GeneratorOpts,
+}
+
+#[derive(Clap)]
+struct ReplayOpts {
+	/// Replay file, gzip compressed or not
+	#[clap(parse(from_os_str))]
+	replay: PathBuf,
+	#[clap(flatten)]
+	output: OutputOpts,
+	/// Write the state after <STEP> moves in the map format instead of
+	/// replaying the solution
+	#[clap(long, value_name = "STEP")]
+	export: Option<usize>,
+}
+
+#[derive(Clap)]
+struct BatchOpts {
+	/// Directory of .map files, or file of puzzles one after the other
+	#[clap(parse(from_os_str))]
+	path: PathBuf,
+	/// Search function to use
+	#[clap(short, long, arg_enum, default_value = "linear")]
+	search: Priorities,
+	#[clap(flatten)]
+	solver: SolverOpts,
+	/// Format of the report
+	#[clap(long, arg_enum, default_value = "csv")]
+	report: Reports,
+	/// Write the report to <OUTPUT> instead of the standard output
+	#[clap(short, long, parse(from_os_str))]
+	output: Option<PathBuf>,
+	/// Do not print the status of each puzzle as it is solved
+	#[clap(short, long)]
+	quiet: bool,
+}
+
+#[derive(Clap)]
+struct BenchOpts {
+	/// Directory of .map files, or file of puzzles one after the other
+	#[clap(parse(from_os_str), required_unless_present = "generate")]
+	path: Option<PathBuf>,
+	/// Use <COUNT> random solvable puzzles of size <GENERATE> instead
+	#[clap(short, long, parse(try_from_str = generator_size), conflicts_with = "path")]
+	generate: Option<u16>,
+	/// Number of random puzzles
+	#[clap(long, default_value = "10")]
+	count: usize,
+	/// Seed of the first random puzzle, the next ones using the following seeds
+	#[clap(long)]
+	seed: Option<u64>,
+	/// Heuristic to compare, can be repeated [default: every heuristic]
+	#[clap(short = 'H', long = "heuristic", parse(try_from_str = heuristic::parse_heuristic), multiple_occurrences = true)]
+	heuristics: Vec<HeuristicExpr>,
+	/// Search function to compare, can be repeated [default: every search function]
+	#[clap(short, long = "search", arg_enum, multiple_occurrences = true)]
+	searches: Vec<Priorities>,
+	#[clap(flatten)]
+	limits: LimitOpts,
+	/// Do not print the status of each puzzle as it is solved
+	#[clap(short, long)]
+	quiet: bool,
+}
+
+#[derive(Clap)]
+struct VerifyOpts {
+	/// Replay file to check
+	#[clap(
+		long,
+		parse(from_os_str),
+		required_unless_present = "moves",
+		conflicts_with = "moves"
+	)]
 	replay: Option<PathBuf>,
+	/// Moves of the blank to check, given as a string such as RRDLU
+	#[clap(long)]
+	moves: Option<String>,
+	/// Puzzle the moves start from, replays are checked against it when given
+	#[clap(flatten)]
+	map: MapOpts,
+}
+
+#[derive(Clap)]
+struct MapOpts {
+	/// Puzzle file, read from the standard input when neither it nor --generate is given
+	#[clap(parse(from_os_str))]
+	map: Option<PathBuf>,
+	/// Use a random puzzle of size <GENERATE>
+	#[clap(short, long, parse(try_from_str = generator_size), conflicts_with = "map")]
+	generate: Option<u16>,
+	#[clap(flatten)]
+	generator: GeneratorOpts,
+}
+
+#[derive(Clap)]
+struct GeneratorOpts {
+	/// Seed of the random puzzle, to generate the same one again
+	#[clap(long)]
+	seed: Option<u64>,
+	/// Only generate solvable puzzles
+	#[clap(long, conflicts_with = "unsolvable")]
+	solvable: bool,
+	/// Only generate unsolvable puzzles
+	#[clap(long)]
+	unsolvable: bool,
+	/// Move the blank <WALK> times from the goal instead of shuffling the
+	/// tiles, fewer moves give easier puzzles
+	#[clap(long)]
+	walk: Option<usize>,
+	/// Only generate puzzles whose optimal solution takes <LENGTH> moves, or
+	/// <MIN>..<MAX> moves, checked with IDA* and the pattern database
+	#[clap(long, parse(try_from_str = moves_range), conflicts_with_all = &["walk", "unsolvable"])]
+	length: Option<RangeInclusive<u16>>,
+}
+
+#[derive(Clap)]
+struct SolverOpts {
+	/// Heuristic function to use: manhatthan, hamming, euclidian, linear-conflict,
+	/// pattern-database, walking-distance, or a combination such as
+	/// max(manhatthan,linear-conflict), sum(..) and weighted(<heuristic>,<weight>)
+	/// with a weight up to 100
+	#[clap(short = 'H', long, parse(try_from_str = heuristic::parse_heuristic), default_value = "manhatthan")]
+	heuristic: HeuristicExpr,
+	#[clap(flatten)]
+	limits: LimitOpts,
+}
+
+#[derive(Clap)]
+struct LimitOpts {
+	/// Stop searching after <MAX_TIME> seconds
+	#[clap(long, parse(try_from_str = seconds))]
+	max_time: Option<Duration>,
+	/// Stop searching after opening <MAX_NODES> states (SI prefixes allowed, e.g. 10M)
+	#[clap(long, parse(try_from_str = si_number))]
+	max_nodes: Option<usize>,
+	/// Stop searching when stored states take about <MAX_MEMORY> bytes (e.g. 2G)
+	#[clap(long, parse(try_from_str = si_number))]
+	max_memory: Option<usize>,
+}
+
+#[derive(Clap)]
+struct OutputOpts {
+	/// Print as text instead of using the terminal interface, which is also
+	/// the case when the output is not a terminal
+	#[clap(long)]
+	no_tui: bool,
+	/// Output format, json never uses the terminal interface
+	#[clap(long, arg_enum, default_value = "text")]
+	format: Formats,
+}
+
+#[derive(Clap)]
+struct SaveOpts {
+	/// Save the solution for replay, gzip compressed if the path ends with .gz
+	#[clap(long, parse(from_os_str))]
+	save: Option<PathBuf>,
+	/// Compress the saved replay with gzip, adding .gz to its path
+	#[clap(long)]
+	compress: bool,
+}
+
+impl LimitOpts {
+	fn is_set(&self) -> bool {
+		self.max_time.is_some() || self.max_nodes.is_some() || self.max_memory.is_some()
+	}
+}
+
+impl From<&LimitOpts> for Limits {
+	fn from(opts: &LimitOpts) -> Self {
+		Limits {
+			time: opts.max_time,
+			nodes: opts.max_nodes,
+			memory: opts.max_memory,
+		}
+	}
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
+impl GeneratorOpts {
+	fn is_set(&self) -> bool {
+		self.seed.is_some()
+			|| self.solvable
+			|| self.unsolvable
+			|| self.walk.is_some()
+			|| self.length.is_some()
+	}
+
+	// Returns the seed along with the map, and the optimal number of moves
+	// when it is known. `any` is used without --solvable and --unsolvable
+	fn generate(
+		&self,
+		size: u16,
+		any: Solvability,
+	) -> Result<(Map, u64, Option<u16>), Box<dyn Error>> {
+		let g = Generator::new(size as usize, self.seed);
+		if let Some(moves) = &self.length {
+			Generator::check_length(size as usize, moves)?;
+			let solved_map = gen_solved_map(size as usize);
+			let pdb = PatternDatabase::new(&solved_map, size);
+			let solver = Solver::new(
+				&solved_map,
+				HRST::PatternDatabase(pdb),
+				Generator::attempt_limits(),
+			);
+			let (map, optimal) = g.generate_length(moves.clone(), &solver)?;
+			return Ok((map, g.seed(), Some(optimal)));
+		}
+		let solvability = match (self.solvable, self.unsolvable) {
+			(true, _) => Solvability::Solvable,
+			(_, true) => Solvability::Unsolvable,
+			_ => any,
+		};
+		let map = match self.walk {
+			Some(moves) => g.generate_walk(moves, solvability),
+			None => g.generate(solvability),
+		};
+		Ok((map, g.seed(), None))
+	}
+}
+
+impl OutputOpts {
+	fn tui(&self) -> bool {
+		!self.no_tui && self.format == Formats::Text && io::stdout().is_terminal()
+	}
+}
+
+impl SaveOpts {
+	fn path(&self) -> Option<PathBuf> {
+		self.save
+			.clone()
+			.map(|path| replay::save_path(path, self.compress))
+	}
+}
+
+fn main() {
 	let opts: Opts = Opts::parse();
-	if let Some(path) = opts.replay {
-		replay(path)
-	} else {
-		solve(opts)
+	let result = match opts.command {
+		Command::Solve(opts) => match &opts.moves {
+			Some(moves) => play_moves(&opts, moves),
+			None => solve(opts),
+		},
+		Command::Play(opts) => play(opts),
+		Command::Generate(opts) => generate(opts),
+		Command::Replay(opts) => replay(opts),
+		Command::Batch(opts) => batch(opts),
+		Command::Bench(opts) => bench(opts),
+		Command::Verify(opts) => verify(opts),
+	};
+	if let Err(e) = result {
+		eprintln!("{}", e);
+		process::exit(1);
 	}
 }
 
-// 2 serves as the null value
 fn generator_size(s: &str) -> Result<u16, String> {
-	si_number_range(s, 2, 15)
+	si_number_range(s, 3, 15)
 }
 
-fn get_map(opts: &Opts) -> Result<Map, Box<dyn Error>> {
-	if opts.generate > 2 {
-		let g = Generator::new(opts.generate as usize);
-		Ok(g.generate())
+// A number of moves or an inclusive range such as 30..35
+fn moves_range(s: &str) -> Result<RangeInclusive<u16>, String> {
+	let parse = |n: &str| {
+		n.parse::<u16>()
+			.map_err(|_| format!("invalid number of moves `{}`", s))
+	};
+	let range = match s.split_once("..") {
+		Some((min, max)) => parse(min)?..=parse(max)?,
+		None => parse(s)?..=parse(s)?,
+	};
+	match range.is_empty() {
+		true => Err(format!("empty range of moves `{}`", s)),
+		false => Ok(range),
+	}
+}
+
+fn seconds(s: &str) -> Result<Duration, String> {
+	s.parse::<f64>()
+		.ok()
+		.and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+		.ok_or_else(|| format!("invalid number of seconds `{}`", s))
+}
+
+// Also returns the seed when the map is generated
+fn get_map(opts: &MapOpts, any: Solvability) -> Result<(Map, Option<u64>), Box<dyn Error>> {
+	if let Some(size) = opts.generate {
+		let (map, seed, _) = opts.generator.generate(size, any)?;
+		eprintln!("Generated puzzle with seed {}", seed);
+		Ok((map, Some(seed)))
+	} else if opts.generator.is_set() {
+		Err("--seed, --solvable, --unsolvable, --walk and --length need --generate".into())
 	} else {
 		let input: String = match &opts.map {
 			Some(filename) => fs::read_to_string(filename)?,
@@ -77,27 +417,230 @@ fn get_map(opts: &Opts) -> Result<Map, Box<dyn Error>> {
 			}
 		};
 
-		let (_, (size, board)) = parser::parse_map(&input).or(Err("Unable to parse map !"))?;
-		Ok(parser::validate_map(size, board)?)
+		Ok((parser::parse_map(&input)?, None))
 	}
 }
 
-fn solve(opts: Opts) -> Result<(), Box<dyn Error>> {
-	let map = get_map(&opts)?;
-	let executor = Executor::new(map, opts.heuristic, initscr());
-	executor.run(opts.search, opts.save, opts.skip)?;
-	endwin();
+fn solve(opts: SolveOpts) -> Result<(), Box<dyn Error>> {
+	let (map, seed) = get_map(&opts.map, Solvability::Any)?;
+	let tui = opts.output.tui();
+	let progress = opts.progress.unwrap_or(match tui {
+		true => Observers::Curses,
+		false => Observers::None,
+	});
+	let save = opts.save.path();
+	let window = if tui { Some(initscr()) } else { None };
+	let result = Executor::new(
+		map,
+		seed,
+		&opts.solver.heuristic,
+		Limits::from(&opts.solver.limits),
+		progress,
+		window,
+	)
+	.and_then(|executor| {
+		executor.run(opts.search, save, opts.skip, opts.output.format)?;
+		Ok(executor.is_optimal(opts.search))
+	});
+	if tui {
+		endwin();
+	}
+	if !result? {
+		eprintln!(
+			"Warning: the solution may not be optimal, only ida with an admissible heuristic, uniform and bidirectional guarantee it"
+		);
+	}
 	Ok(())
 }
 
-fn replay(replay_file: PathBuf) -> Result<(), Box<dyn Error>> {
-	let file = File::open(&replay_file)?;
-	let solution: Solution<State> = if replay_file.extension() == Some(OsStr::new("gz")) {
-		bincode::deserialize_from(GzDecoder::new(file))?
-	} else {
-		bincode::deserialize_from(file)?
+fn play(opts: PlayOpts) -> Result<(), Box<dyn Error>> {
+	if !io::stdout().is_terminal() {
+		return Err("Play mode needs a terminal".into());
+	}
+	let (map, seed) = get_map(&opts.map, Solvability::Solvable)?;
+	let solved_map = gen_solved_map(map.size as usize);
+	let heuristic = opts.solver.heuristic.build(&solved_map)?;
+	let limits = match opts.solver.limits.is_set() {
+		true => Limits::from(&opts.solver.limits),
+		false => game::HINT_LIMITS,
 	};
-	solution.print(&initscr());
+	let solver = Solver::new(&solved_map, heuristic, limits);
+	if !Solver::is_solvable(&map) {
+		return Err("Puzzle is not solvable !".into());
+	}
+	let mut game = Game::new(map);
+	let window = initscr();
+	game.run(&window, &solver);
 	endwin();
+	if let Some(path) = opts.save.path() {
+		let metadata = Metadata {
+			seed,
+			..Metadata::default()
+		};
+		Replay::new(game.solution(), metadata).save(&path)?;
+	}
+	Ok(())
+}
+
+fn generate(opts: GenerateOpts) -> Result<(), Box<dyn Error>> {
+	let (map, seed, optimal) = opts.generator.generate(opts.size, Solvability::Any)?;
+	let mut comments = vec![
+		Comment::Solvable(Solver::is_solvable(&map)),
+		Comment::Seed(seed),
+	];
+	comments.extend(optimal.map(Comment::Optimal));
+	let mut text = Vec::new();
+	map.write(&mut text, &comments)?;
+	match opts.output {
+		Some(path) => fs::write(path, text)?,
+		None => io::stdout().lock().write_all(&text)?,
+	}
+	Ok(())
+}
+
+fn replay(opts: ReplayOpts) -> Result<(), Box<dyn Error>> {
+	let replay = Replay::load(&opts.replay)?;
+	match opts.export {
+		Some(step) => export(&replay, step),
+		None => show(&replay.solution, &replay.metadata, &opts.output),
+	}
+}
+
+// Writes a state of the solution to the standard output in the map format
+fn export(replay: &Replay, step: usize) -> Result<(), Box<dyn Error>> {
+	let solution = &replay.solution;
+	let moves = solution.states.len() - 1;
+	let state = match solution.states.get(step) {
+		Some(state) => state,
+		None => return Err(format!("Step {} is past the last move {}", step, moves).into()),
+	};
+	let map = Map {
+		size: solution.size,
+		board: state.board.clone(),
+	};
+	let mut comments = vec![Comment::Text(format!(
+		"State after {} of {} moves, {} more to solve it",
+		step,
+		moves,
+		moves - step
+	))];
+	if step == 0 {
+		comments.extend(replay.metadata.seed.map(Comment::Seed));
+	}
+	map.write(&mut io::stdout().lock(), &comments)?;
+	Ok(())
+}
+
+fn batch(opts: BatchOpts) -> Result<(), Box<dyn Error>> {
+	let entries = batch::load(&opts.path)?;
+	let rows = batch::solve(
+		entries,
+		&opts.solver.heuristic,
+		Limits::from(&opts.solver.limits),
+		opts.search,
+		opts.quiet,
+	);
+	let summary = batch::summarize(&rows);
+	let mut out: Box<dyn Write> = match opts.output {
+		Some(path) => Box::new(io::BufWriter::new(fs::File::create(path)?)),
+		None => Box::new(io::stdout()),
+	};
+	match opts.report {
+		Reports::Csv => batch::write_csv(&mut out, &rows, &summary)?,
+		Reports::Json => batch::write_json(&mut out, &rows, &summary)?,
+	}
+	out.flush()?;
+	Ok(())
+}
+
+fn bench(opts: BenchOpts) -> Result<(), Box<dyn Error>> {
+	let entries = match (&opts.path, opts.generate) {
+		(Some(path), _) => batch::load(path)?,
+		(None, Some(size)) => {
+			let first = opts.seed.unwrap_or_else(rand::random);
+			(0..opts.count as u64)
+				.map(|i| {
+					let seed = first.wrapping_add(i);
+					let g = Generator::new(size as usize, Some(seed));
+					batch::Entry {
+						name: format!("seed {}", seed),
+						map: Ok(g.generate(Solvability::Solvable)),
+					}
+				})
+				.collect()
+		}
+		(None, None) => unreachable!(),
+	};
+	let mut heuristics = opts.heuristics;
+	if heuristics.is_empty() {
+		heuristics = Heuristics::VARIANTS
+			.iter()
+			.map(|name| heuristic::parse_heuristic(name))
+			.collect::<Result<_, _>>()?;
+		// Aliases are variants too
+		heuristics.dedup_by_key(|h| h.to_string());
+	}
+	let mut searches = opts.searches;
+	if searches.is_empty() {
+		searches = Priorities::VARIANTS
+			.iter()
+			.filter_map(|name| Priorities::from_str(name, false).ok())
+			.collect();
+	}
+	let limits = match opts.limits.is_set() {
+		true => Limits::from(&opts.limits),
+		false => bench::DEFAULT_LIMITS,
+	};
+	let runs = bench::run(&entries, &heuristics, &searches, limits, opts.quiet);
+	let optimal = bench::optimal_lengths(&entries, &runs, limits);
+	let flagged = bench::non_optimal(&runs, &optimal);
+	bench::print_table(&mut io::stdout().lock(), &runs, &flagged)?;
+	Ok(())
+}
+
+fn verify(opts: VerifyOpts) -> Result<(), Box<dyn Error>> {
+	let moves = match (&opts.replay, &opts.moves) {
+		(Some(path), _) => {
+			let replay = Replay::load(path)?;
+			let solution = replay.solution;
+			// Without a map, only the replay itself is checked
+			let start = match opts.map.map.is_some() || opts.map.generate.is_some() {
+				true => Some(State::from(get_map(&opts.map, Solvability::Any)?.0)),
+				false => None,
+			};
+			verify::verify_states(&solution.states, solution.size, start.as_ref())?
+		}
+		(None, Some(moves)) => {
+			let (map, _) = get_map(&opts.map, Solvability::Any)?;
+			let size = map.size;
+			verify::verify_moves(State::from(map), &state::parse_moves(moves)?, size)?
+		}
+		(None, None) => unreachable!(),
+	};
+	println!("Valid solution in {} moves", moves);
+	Ok(())
+}
+
+fn play_moves(opts: &SolveOpts, moves: &str) -> Result<(), Box<dyn Error>> {
+	let (map, _) = get_map(&opts.map, Solvability::Any)?;
+	let size = map.size;
+	let moves = state::parse_moves(moves)?;
+	let solution = Solution::from_moves(State::from(map), &moves, size, 0, 0)?;
+	show(&solution, &Metadata::default(), &opts.output)
+}
+
+fn show(
+	solution: &Solution<State>,
+	metadata: &Metadata,
+	output: &OutputOpts,
+) -> Result<(), Box<dyn Error>> {
+	if output.format == Formats::Json {
+		solution.print_json(&mut io::stdout().lock(), metadata)?;
+	} else if output.tui() {
+		solution.print(&initscr());
+		endwin();
+	} else {
+		solution.print_text(&mut io::stdout().lock(), true)?;
+	}
 	Ok(())
 }
@@ -1,4 +1,5 @@
 use std::fmt::{Debug, Display, Formatter};
+use std::io::{self, Write};
 
 #[derive(PartialEq, Clone)]
 pub struct Map {
@@ -6,7 +7,7 @@ pub struct Map {
 	pub board: Vec<u16>,
 }
 
-pub fn fmt_board(board: &Vec<u16>, size: usize, f: &mut Formatter<'_>) -> std::fmt::Result {
+pub fn fmt_board(board: &[u16], size: usize, f: &mut Formatter<'_>) -> std::fmt::Result {
 	let width = match board.iter().max() {
 		Some(w) => w.to_string().len(),
 		None => return Ok(()),
@@ -36,6 +37,39 @@ impl Debug for Map {
 	}
 }
 
+// Written as `# ` lines before the map, ignored by the parser
+pub enum Comment {
+	Solvable(bool),
+	Seed(u64),
+	// Number of moves of an optimal solution
+	Optimal(u16),
+	Text(String),
+}
+
+impl Display for Comment {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Comment::Solvable(true) => f.write_str("This puzzle is solvable"),
+			Comment::Solvable(false) => f.write_str("This puzzle is unsolvable"),
+			Comment::Seed(seed) => write!(f, "Seed: {}", seed),
+			Comment::Optimal(moves) => write!(f, "Optimal solution: {} moves", moves),
+			Comment::Text(text) => f.write_str(text),
+		}
+	}
+}
+
+impl Map {
+	// Writes the format read by parser::parse_map
+	pub fn write(&self, out: &mut impl Write, comments: &[Comment]) -> io::Result<()> {
+		for comment in comments {
+			for line in comment.to_string().lines() {
+				writeln!(out, "# {}", line)?;
+			}
+		}
+		write!(out, "{}\n{}", self.size, self)
+	}
+}
+
 enum Direction {
 	Right,
 	Down,
@@ -110,7 +144,26 @@ pub fn gen_solved_map(size: usize) -> Map {
 #[cfg(test)]
 mod tests {
 	use super::gen_solved_map;
-	use super::Map;
+	use super::{Comment, Map};
+	use crate::parser::parse_map;
+
+	#[test]
+	fn write_round_trip() {
+		let map = gen_solved_map(4);
+		let mut out = Vec::new();
+		let comments = [
+			Comment::Solvable(true),
+			Comment::Seed(42),
+			Comment::Optimal(0),
+			Comment::Text("two\nlines".to_string()),
+		];
+		map.write(&mut out, &comments).unwrap();
+		let text = String::from_utf8(out).unwrap();
+		assert!(text.starts_with(
+			"# This puzzle is solvable\n# Seed: 42\n# Optimal solution: 0 moves\n# two\n# lines\n4\n 1  2  3  4\n"
+		));
+		assert_eq!(parse_map(&text).unwrap(), map);
+	}
 
 	#[test]
 	fn gen_map_3() {
@@ -9,7 +9,7 @@ pub struct Node {
 }
 
 impl Node {
-	pub fn collect_parents(&self, nodes: &Vec<Self>) -> Vec<Rc<State>> {
+	pub fn collect_parents(&self, nodes: &[Self]) -> Vec<Rc<State>> {
 		let mut states = Vec::new();
 		let mut current_node: &Self = self;
 		loop {
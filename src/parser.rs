@@ -1,75 +1,454 @@
 use crate::map::Map;
-use nom::branch::alt;
-use nom::bytes::complete::{is_not, take_while1};
-use nom::character::complete;
-use nom::character::complete::{char, multispace0};
-use nom::combinator::value;
-use nom::multi::{separated_list0, separated_list1};
-use nom::sequence::pair;
-use nom::IResult;
-
-fn consume_line(line: &str) -> IResult<&str, Vec<u16>> {
-	separated_list1(take_while1(|c| c == ' '), complete::u16)(line)
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::iter::Peekable;
+
+// Where a problem was found, line and column starting at 1
+#[derive(Debug, Clone, PartialEq)]
+pub struct Position {
+	pub line: usize,
+	pub column: usize,
+	// The whole line, to show it in diagnostics
+	pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+	// What should have been there, e.g. "a number"
+	Expected(&'static str),
+	InvalidSize(u64),
+	OutOfRange {
+		value: u64,
+		max: u16,
+	},
+	// The first line and column of the tile, and a tile missing because of it
+	DuplicateTile {
+		tile: u16,
+		first: (usize, usize),
+		missing: u16,
+	},
+	ShortRow {
+		expected: u16,
+		found: usize,
+	},
+	LongRow {
+		expected: u16,
+		found: usize,
+	},
+	MissingRows {
+		expected: u16,
+		found: usize,
+	},
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+	pub problem: Problem,
+	// None when the problem is about the whole board
+	pub position: Option<Position>,
 }
 
-fn parse_comments(input: &str) -> IResult<&str, ()> {
-	let mut remaining = input;
-	loop {
-		if let Ok((rem, ())) = parse_comment(remaining) {
-			if rem.len() != remaining.len() {
-				// Continue only if something was consumed
-				remaining = rem;
-				continue;
+impl Display for Problem {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Problem::Expected(what) => write!(f, "expected {}", what),
+			Problem::InvalidSize(size) => {
+				write!(f, "invalid size {}, expected 2 to 255", size)
+			}
+			Problem::OutOfRange { value, max } => {
+				write!(
+					f,
+					"value {} out of range, tiles go from 0 to {}",
+					value, max
+				)
+			}
+			Problem::DuplicateTile {
+				tile,
+				first,
+				missing,
+			} => write!(
+				f,
+				"duplicate tile {}, already on line {}, column {}, so tile {} is missing",
+				tile, first.0, first.1, missing
+			),
+			Problem::ShortRow { expected, found } => write!(
+				f,
+				"row too short, expected {} values but found {}",
+				expected, found
+			),
+			Problem::LongRow { expected, found } => write!(
+				f,
+				"row too long, expected {} values but found {}",
+				expected, found
+			),
+			Problem::MissingRows { expected, found } => {
+				write!(f, "expected {} rows but found {}", expected, found)
 			}
 		}
-		break;
 	}
-	Ok((remaining, ()))
 }
-fn parse_comment(input: &str) -> IResult<&str, ()> {
-	alt((
-		value((), pair(char('#'), is_not("\n\r"))),
-		value((), multispace0),
-	))(input)
+
+impl ParseError {
+	// Problem and position on a single line, without the offending line
+	pub fn message(&self) -> String {
+		match &self.position {
+			Some(p) => format!("Line {}, column {}: {}", p.line, p.column, self.problem),
+			None => format!("{}", self.problem)
+				.chars()
+				.enumerate()
+				.map(|(i, c)| if i == 0 { c.to_ascii_uppercase() } else { c })
+				.collect(),
+		}
+	}
 }
 
-pub fn parse_map(map: &str) -> IResult<&str, (u16, Vec<Vec<u16>>)> {
-	let (remaining, _) = parse_comments(map)?;
-	let (remaining, size) = complete::u16(remaining)?;
-	let (remaining, _) = parse_comments(remaining)?; // Remove the trailing newline after the map size
-	let (remaining, board) = separated_list0(parse_comments, consume_line)(remaining)?;
+impl Display for ParseError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.write_str(&self.message())?;
+		if let Some(p) = &self.position {
+			let number = p.line.to_string();
+			write!(
+				f,
+				"\n {} | {}\n {} | {}^",
+				number,
+				p.text,
+				" ".repeat(number.len()),
+				" ".repeat(p.column - 1)
+			)?;
+		}
+		Ok(())
+	}
+}
+
+impl Error for ParseError {}
 
-	Ok((remaining, (size, board)))
+// A line with its values, comments and blank lines are skipped
+#[derive(Clone)]
+struct Line<'a> {
+	number: usize,
+	text: &'a str,
+	// Column and text of each value
+	tokens: Vec<(usize, &'a str)>,
 }
 
-pub fn validate_map(size: u16, board: Vec<Vec<u16>>) -> Result<Map, &'static str> {
-	if board.len() != size as usize {
-		return Err("Board has invalid size");
+impl<'a> Line<'a> {
+	fn error(&self, column: usize, problem: Problem) -> ParseError {
+		ParseError {
+			problem,
+			position: Some(Position {
+				line: self.number,
+				column,
+				text: self.text.to_string(),
+			}),
+		}
 	}
-	let mut map = Map {
-		size,
-		board: Vec::with_capacity(size as usize),
+
+	// Column just after the last value
+	fn end(&self) -> usize {
+		match self.tokens.last() {
+			Some((column, token)) => column + token.chars().count(),
+			None => 1,
+		}
+	}
+}
+
+fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+	input.lines().enumerate().filter_map(|(i, text)| {
+		let content = match text.find('#') {
+			Some(comment) => &text[..comment],
+			None => text,
+		};
+		let tokens: Vec<(usize, &str)> = content
+			.split_whitespace()
+			.map(|token| {
+				let offset = token.as_ptr() as usize - text.as_ptr() as usize;
+				(text[..offset].chars().count() + 1, token)
+			})
+			.collect();
+		match tokens.is_empty() {
+			true => None,
+			false => Some(Line {
+				number: i + 1,
+				text,
+				tokens,
+			}),
+		}
+	})
+}
+
+fn number(line: &Line, (column, token): (usize, &str)) -> Result<u64, ParseError> {
+	match token.chars().all(|c| c.is_ascii_digit()) {
+		// Too many digits for a u64 is out of range as well
+		true => Ok(token.parse().unwrap_or(u64::MAX)),
+		false => Err(line.error(column, Problem::Expected("a number"))),
+	}
+}
+
+// Reads the size line and the <size> rows following it
+fn parse_board<'a>(
+	lines: &mut Peekable<impl Iterator<Item = Line<'a>>>,
+) -> Result<Map, ParseError> {
+	let line = match lines.next() {
+		Some(line) => line,
+		None => {
+			return Err(ParseError {
+				problem: Problem::Expected("the size of the puzzle"),
+				position: None,
+			})
+		}
 	};
-	let mut validator = vec![false; (size * size) as usize];
-	for v in board {
-		if v.len() != size as usize {
-			return Err("Board has invalid size");
+	let size = number(&line, line.tokens[0])?;
+	if !(2..=255).contains(&size) {
+		return Err(line.error(line.tokens[0].0, Problem::InvalidSize(size)));
+	}
+	if let Some(&(column, _)) = line.tokens.get(1) {
+		return Err(line.error(
+			column,
+			Problem::Expected("the end of the line after the size"),
+		));
+	}
+	let size = size as u16;
+	let max = size * size - 1;
+
+	let mut board = Vec::with_capacity(max as usize + 1);
+	// Line and column of each tile already read
+	let mut seen: Vec<Option<(usize, usize)>> = vec![None; max as usize + 1];
+	// Reported once the board is read, to know which tile is missing
+	let mut duplicate = None;
+	let mut last = line;
+	for row in 0..size as usize {
+		let line = match lines.next() {
+			Some(line) => line,
+			None => {
+				return Err(last.error(
+					last.end(),
+					Problem::MissingRows {
+						expected: size,
+						found: row,
+					},
+				))
+			}
+		};
+		let found = line.tokens.len();
+		if found > size as usize {
+			let column = line.tokens[size as usize].0;
+			return Err(line.error(
+				column,
+				Problem::LongRow {
+					expected: size,
+					found,
+				},
+			));
 		}
-		for item in v.iter() {
-			if (*item as usize) < validator.len() {
-				validator[*item as usize] = true;
-			} else {
-				return Err("Invalid value in board");
+		for &token in line.tokens.iter() {
+			let value = number(&line, token)?;
+			if value > max as u64 {
+				return Err(line.error(token.0, Problem::OutOfRange { value, max }));
 			}
+			let tile = value as u16;
+			match seen[tile as usize] {
+				Some(first) if duplicate.is_none() => {
+					duplicate = Some(line.error(
+						token.0,
+						Problem::DuplicateTile {
+							tile,
+							first,
+							missing: 0,
+						},
+					))
+				}
+				Some(_) => {}
+				None => seen[tile as usize] = Some((line.number, token.0)),
+			}
+			board.push(tile);
+		}
+		if found < size as usize {
+			return Err(line.error(
+				line.end(),
+				Problem::ShortRow {
+					expected: size,
+					found,
+				},
+			));
 		}
-		map.board.extend(v);
+		last = line;
 	}
-	for item in validator.iter() {
-		if !(*item) {
-			return Err("Invalid Board");
+	if let Some(mut e) = duplicate {
+		if let Problem::DuplicateTile { missing, .. } = &mut e.problem {
+			*missing = seen.iter().position(Option::is_none).unwrap_or_default() as u16;
 		}
+		return Err(e);
 	}
-	Ok(map)
+	Ok(Map { size, board })
 }
 
-//TODO: Test
+pub fn parse_map(input: &str) -> Result<Map, ParseError> {
+	let mut lines = lines(input).peekable();
+	let map = parse_board(&mut lines)?;
+	match lines.next() {
+		Some(line) => Err(line.error(line.tokens[0].0, Problem::Expected("the end of the file"))),
+		None => Ok(map),
+	}
+}
+
+// Checks a board read from elsewhere, such as a replay, as parse_map would.
+// Positions are those of the board written in the map format.
+pub fn check_board(size: u16, board: Vec<u16>) -> Result<Map, ParseError> {
+	let mut text = format!("{}\n", size);
+	for row in board.chunks(size.max(1) as usize) {
+		let row: Vec<String> = row.iter().map(u16::to_string).collect();
+		text.push_str(&row.join(" "));
+		text.push('\n');
+	}
+	parse_map(&text)
+}
+
+// Maps one after the other, each board being read as <size> lines. After an
+// error, reading starts again on the next line holding a single value, taken as
+// the size of the next map.
+pub fn parse_maps(input: &str) -> Vec<Result<Map, ParseError>> {
+	let all: Vec<Line> = lines(input).collect();
+	let mut maps = Vec::new();
+	let mut start = 0;
+	while start < all.len() {
+		let mut lines = all[start..].iter().cloned().peekable();
+		let map = parse_board(&mut lines);
+		start = match map {
+			Ok(_) => all.len() - lines.count(),
+			Err(_) => (start + 1..all.len())
+				.find(|&i| all[i].tokens.len() == 1)
+				.unwrap_or(all.len()),
+		};
+		maps.push(map);
+	}
+	maps
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn problem(input: &str) -> (Problem, Option<(usize, usize)>) {
+		let e = parse_map(input).unwrap_err();
+		(e.problem, e.position.map(|p| (p.line, p.column)))
+	}
+
+	#[test]
+	fn valid() {
+		let map =
+			parse_map("# This puzzle is solvable\n3\n3 2 8 # comment\n\n4 1 5\n 6  7 0\n").unwrap();
+		assert_eq!(map.size, 3);
+		assert_eq!(map.board, vec![3, 2, 8, 4, 1, 5, 6, 7, 0]);
+	}
+
+	#[test]
+	fn problems() {
+		assert_eq!(
+			problem("3\n3 2 8\n4 1 5\n6 7 x\n"),
+			(Problem::Expected("a number"), Some((4, 5)))
+		);
+		assert_eq!(
+			problem("3\n3 2 8\n4 2 5\n6 7 0\n"),
+			(
+				Problem::DuplicateTile {
+					tile: 2,
+					first: (2, 3),
+					missing: 1
+				},
+				Some((3, 3))
+			)
+		);
+		assert_eq!(
+			problem("3\n3 2 8\n4 1 5\n6 9 0\n"),
+			(Problem::OutOfRange { value: 9, max: 8 }, Some((4, 3)))
+		);
+		assert_eq!(
+			problem("3\n3 2 8\n4 1\n6 7 0\n"),
+			(
+				Problem::ShortRow {
+					expected: 3,
+					found: 2
+				},
+				Some((3, 4))
+			)
+		);
+		assert_eq!(
+			problem("3\n3 2 8 5\n4 1\n6 7 0\n"),
+			(
+				Problem::LongRow {
+					expected: 3,
+					found: 4
+				},
+				Some((2, 7))
+			)
+		);
+		assert_eq!(
+			problem("3\n3 2 8\n4 1 5\n"),
+			(
+				Problem::MissingRows {
+					expected: 3,
+					found: 2
+				},
+				Some((3, 6))
+			)
+		);
+		assert_eq!(problem("1\n0\n"), (Problem::InvalidSize(1), Some((1, 1))));
+		assert_eq!(
+			problem("3\n3 2 8\n4 1 5\n6 7 0\n1\n"),
+			(Problem::Expected("the end of the file"), Some((5, 1)))
+		);
+	}
+
+	#[test]
+	fn diagnostic() {
+		let e = parse_map("2\n1 3\n1 0\n").unwrap_err();
+		assert_eq!(
+			e.to_string(),
+			"Line 3, column 1: duplicate tile 1, already on line 2, column 1, so tile 2 is missing\n 3 | 1 0\n   | ^"
+		);
+	}
+
+	#[test]
+	fn check_boards() {
+		assert!(check_board(2, vec![1, 2, 3, 0]).is_ok());
+		let e = check_board(3, vec![1, 2, 3]).unwrap_err();
+		assert_eq!(
+			e.problem,
+			Problem::MissingRows {
+				expected: 3,
+				found: 1
+			}
+		);
+		let e = check_board(2, vec![1, 2, 3, 3]).unwrap_err();
+		assert!(matches!(e.problem, Problem::DuplicateTile { tile: 3, .. }));
+		let e = check_board(0, vec![]).unwrap_err();
+		assert_eq!(e.problem, Problem::InvalidSize(0));
+	}
+
+	#[test]
+	fn several_maps() {
+		let maps = parse_maps("2\n1 2\n3 0\n# second\n2\n1 1\n3 0\n2\n0 1\n2 3\nx\n");
+		assert_eq!(maps.len(), 4);
+		assert!(maps[0].is_ok());
+		assert!(maps[1].is_err());
+		assert_eq!(maps[2].as_ref().unwrap().board, vec![0, 1, 2, 3]);
+		assert!(maps[3].is_err());
+	}
+
+	#[test]
+	fn resync_after_error() {
+		let maps = parse_maps("2\n1 2 3\n3 0\n2\n1 2\n3 0\n2\n0 1\n2 3\n");
+		assert_eq!(maps.len(), 3);
+		assert!(matches!(
+			maps[0].as_ref().unwrap_err().problem,
+			Problem::LongRow { .. }
+		));
+		assert_eq!(maps[1].as_ref().unwrap().board, vec![1, 2, 3, 0]);
+		assert_eq!(maps[2].as_ref().unwrap().board, vec![0, 1, 2, 3]);
+		// The rows left after the error are skipped
+		let maps = parse_maps("2\n1 2\nx 1\n3 0\n2\n1 2\n3 0\n");
+		assert_eq!(maps.len(), 2);
+		assert!(maps[0].is_err());
+		assert!(maps[1].is_ok());
+	}
+}
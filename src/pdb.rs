@@ -0,0 +1,280 @@
+use crate::map::Map;
+use crate::state::State;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process;
+
+// Additive disjoint pattern database: the tiles are split in groups, and for
+// each group a table gives the number of moves of the group's own tiles needed
+// to bring them home. Moves of other tiles are free, so the sum stays admissible.
+pub struct PatternDatabase {
+	cells: usize,
+	patterns: Vec<Pattern>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Pattern {
+	tiles: Vec<u16>,
+	table: Vec<u8>,
+}
+
+impl PatternDatabase {
+	// Load the tables from the cache directory, building and saving them if needed
+	pub fn new(solved_map: &Map, size: u16) -> Self {
+		let path = cache_dir().map(|dir| dir.join(format!("pdb-{}.bin.gz", size)));
+		Self::cached(solved_map, path.as_deref())
+	}
+
+	// The cache is only an optimization, failing to write it is not an error
+	fn cached(solved_map: &Map, path: Option<&Path>) -> Self {
+		let cells = solved_map.board.len();
+		let partition = Self::partition(solved_map);
+		if let Some(patterns) = path.and_then(|p| Self::load(p, &partition, cells)) {
+			return Self { cells, patterns };
+		}
+
+		let pdb = Self::build(solved_map);
+		if let Some(path) = path {
+			if let Err(e) = Self::save(path, &pdb.patterns) {
+				eprintln!(
+					"Warning: unable to cache the pattern database in {}: {}",
+					path.display(),
+					e
+				);
+			}
+		}
+		pdb
+	}
+
+	pub fn build(solved_map: &Map) -> Self {
+		let patterns = Self::partition(solved_map)
+			.into_iter()
+			.map(|tiles| Pattern::build(solved_map, tiles))
+			.collect();
+
+		Self {
+			cells: solved_map.board.len(),
+			patterns,
+		}
+	}
+
+	// 4-4 for 3x3, 6-6-3 for 4x4, 5-5-5-5-4 for 5x5 and groups of 3 above,
+	// tiles being taken in the order of their goal position
+	fn partition(solved_map: &Map) -> Vec<Vec<u16>> {
+		let group = match solved_map.size {
+			3 => 4,
+			4 => 6,
+			5 => 5,
+			_ => 3,
+		};
+		let tiles: Vec<u16> = solved_map
+			.board
+			.iter()
+			.copied()
+			.filter(|&t| t != 0)
+			.collect();
+
+		tiles.chunks(group).map(|c| c.to_vec()).collect()
+	}
+
+	// None when the cache is missing, unreadable or built for other tables
+	fn load(path: &Path, partition: &[Vec<u16>], cells: usize) -> Option<Vec<Pattern>> {
+		let file = File::open(path).ok()?;
+		let patterns: Vec<Pattern> = bincode::deserialize_from(GzDecoder::new(file)).ok()?;
+		let matches = patterns.len() == partition.len()
+			&& patterns.iter().zip(partition).all(|(p, tiles)| {
+				&p.tiles == tiles && p.table.len() == cells.pow(tiles.len() as u32)
+			});
+
+		if matches {
+			Some(patterns)
+		} else {
+			None
+		}
+	}
+
+	// Written to a temporary file first, so that an interrupted run cannot
+	// leave a truncated cache behind
+	fn save(path: &Path, patterns: &[Pattern]) -> Result<(), Box<dyn Error>> {
+		if let Some(dir) = path.parent() {
+			fs::create_dir_all(dir)?;
+		}
+		let mut tmp = path.as_os_str().to_owned();
+		tmp.push(format!(".{}.tmp", process::id()));
+		let tmp = PathBuf::from(tmp);
+		let result = (|| -> Result<(), Box<dyn Error>> {
+			let mut file = GzEncoder::new(File::create(&tmp)?, Compression::default());
+			bincode::serialize_into(&mut file, patterns)?;
+			file.finish()?.flush()?;
+			fs::rename(&tmp, path)?;
+			Ok(())
+		})();
+		if result.is_err() {
+			let _ = fs::remove_file(&tmp);
+		}
+		result
+	}
+
+	pub fn compute_score(&self, state: &State) -> u16 {
+		let mut positions = vec![0; state.board.len()];
+		for (i, item) in state.board.iter().enumerate() {
+			positions[*item as usize] = i;
+		}
+
+		let mut score: u16 = 0;
+		for pattern in self.patterns.iter() {
+			let mut index = 0;
+			for tile in pattern.tiles.iter().rev() {
+				index = index * self.cells + positions[*tile as usize];
+			}
+			score += pattern.table[index] as u16;
+		}
+		score
+	}
+}
+
+impl Pattern {
+	// Backward 0-1 BFS from the goal over (tiles positions, blank position).
+	// A state is encoded in base `cells`, tile i being digit i and the blank the
+	// last digit, so that `index % pattern_size` is the table entry.
+	fn build(solved_map: &Map, tiles: Vec<u16>) -> Self {
+		let size = solved_map.size as usize;
+		let cells = solved_map.board.len();
+		let powers: Vec<usize> = (0..=tiles.len()).map(|i| cells.pow(i as u32)).collect();
+		let pattern_size = powers[tiles.len()];
+		assert!(pattern_size * cells <= u32::MAX as usize);
+
+		let goal_of = |tile: u16| solved_map.board.iter().position(|&t| t == tile).unwrap();
+		let mut start = goal_of(0) * pattern_size;
+		for (i, tile) in tiles.iter().enumerate() {
+			start += goal_of(*tile) * powers[i];
+		}
+
+		let mut table = vec![u8::MAX; pattern_size];
+		let mut visited = vec![0u64; (pattern_size * cells).div_ceil(64)];
+		let mut current: Vec<u32> = vec![start as u32];
+		let mut cost: u8 = 0;
+
+		while !current.is_empty() {
+			let mut next: Vec<u32> = Vec::new();
+			while let Some(index) = current.pop() {
+				let index = index as usize;
+				if visited[index / 64] & (1 << (index % 64)) != 0 {
+					continue;
+				}
+				visited[index / 64] |= 1 << (index % 64);
+
+				let pattern_index = index % pattern_size;
+				if table[pattern_index] == u8::MAX {
+					table[pattern_index] = cost;
+				}
+
+				let blank = index / pattern_size;
+				let (row, col) = (blank / size, blank % size);
+				let targets = [
+					(row > 0).then(|| blank - size),
+					(row < size - 1).then(|| blank + size),
+					(col > 0).then(|| blank - 1),
+					(col < size - 1).then(|| blank + 1),
+				];
+				for target in targets.iter().flatten() {
+					let mut child = pattern_index + target * pattern_size;
+					let tile = (0..tiles.len()).find(|&i| (index / powers[i]) % cells == *target);
+					let queue = match tile {
+						Some(i) => {
+							child = child + blank * powers[i] - target * powers[i];
+							&mut next
+						}
+						None => &mut current,
+					};
+					if visited[child / 64] & (1 << (child % 64)) == 0 {
+						queue.push(child as u32);
+					}
+				}
+			}
+			current = next;
+			cost += 1;
+		}
+
+		Self { tiles, table }
+	}
+}
+
+fn cache_dir() -> Option<PathBuf> {
+	match env::var_os("NPUZZLE_CACHE") {
+		Some(dir) => Some(PathBuf::from(dir)),
+		None => env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache").join("n-puzzle")),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::heuristic::Manhatthan;
+	use crate::map::gen_solved_map;
+	use crate::state::Point;
+
+	#[test]
+	fn pdb_solved() {
+		let solved = gen_solved_map(3);
+		let pdb = PatternDatabase::build(&solved);
+		assert_eq!(pdb.compute_score(&State::from(solved)), 0);
+	}
+
+	#[test]
+	fn pdb_one_move() {
+		let solved = gen_solved_map(3);
+		let pdb = PatternDatabase::build(&solved);
+		let state = State::from(Map {
+			size: 3,
+			board: vec![1, 2, 3, 8, 4, 0, 7, 6, 5],
+		});
+		assert_eq!(pdb.compute_score(&state), 1);
+	}
+
+	#[test]
+	fn pdb_dominates_manhattan() {
+		let solved = gen_solved_map(3);
+		let pdb = PatternDatabase::build(&solved);
+		let state = State::from(Map {
+			size: 3,
+			board: vec![3, 2, 8, 4, 1, 5, 6, 7, 0],
+		});
+		let mut manhattan = 0;
+		for (i, item) in state.board.iter().enumerate() {
+			if *item != 0 {
+				let goal = solved.board.iter().position(|t| t == item).unwrap() as u16;
+				manhattan +=
+					Manhatthan::dist(&Point::from_1d(i as u16, 3), &Point::from_1d(goal, 3));
+			}
+		}
+		assert!(pdb.compute_score(&state) >= manhattan);
+	}
+
+	#[test]
+	fn pdb_cache() {
+		let solved = gen_solved_map(3);
+		let dir = std::env::temp_dir().join(format!("n-puzzle-pdb-{}", process::id()));
+		let path = dir.join("pdb-3.bin.gz");
+		let partition = PatternDatabase::partition(&solved);
+
+		PatternDatabase::cached(&solved, Some(&path));
+		assert!(PatternDatabase::load(&path, &partition, 9).is_some());
+		// Tables of another size are rebuilt
+		assert!(PatternDatabase::load(&path, &partition, 16).is_none());
+
+		// A cache that cannot be written is not an error
+		let blocked = dir.join("pdb-3.bin.gz").join("pdb-3.bin.gz");
+		let pdb = PatternDatabase::cached(&solved, Some(&blocked));
+		assert_eq!(pdb.compute_score(&State::from(solved)), 0);
+		fs::remove_dir_all(dir).unwrap();
+	}
+}
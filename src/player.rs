@@ -0,0 +1,186 @@
+use crate::solver::Solution;
+use crate::state::State;
+use pancurses::{curs_set, noecho, Input, Window};
+use std::cmp::{max, min};
+use std::time::Duration;
+
+const MIN_INTERVAL: Duration = Duration::from_millis(10);
+const MAX_INTERVAL: Duration = Duration::from_secs(2);
+const HELP: &str = "space: pause, left/right: step, +/-: speed, home/end: jump, q: quit";
+
+// Position and speed of a replay, driven by the keys of the player
+pub struct Player {
+	index: usize,
+	last: usize,
+	paused: bool,
+	interval: Duration,
+}
+
+impl Player {
+	pub fn new(moves: usize) -> Self {
+		Self {
+			index: 0,
+			last: moves,
+			paused: false,
+			// Same pace as the old non-interactive replay
+			interval: max(
+				min(
+					Duration::from_secs(20) / max(moves, 1) as u32,
+					Duration::from_millis(250),
+				),
+				MIN_INTERVAL,
+			),
+		}
+	}
+
+	// Returns false when the player should quit
+	pub fn handle(&mut self, input: Option<Input>) -> bool {
+		match input {
+			// No key before the interval elapsed
+			None if !self.paused => self.forward(),
+			Some(Input::Character(' ')) => {
+				if self.index == self.last {
+					self.index = 0;
+				}
+				self.paused = !self.paused;
+			}
+			Some(Input::KeyRight) => {
+				self.paused = true;
+				self.forward();
+			}
+			Some(Input::KeyLeft) => {
+				self.paused = true;
+				self.index = self.index.saturating_sub(1);
+			}
+			Some(Input::Character('+')) => self.interval = max(self.interval / 2, MIN_INTERVAL),
+			Some(Input::Character('-')) => self.interval = min(self.interval * 2, MAX_INTERVAL),
+			Some(Input::KeyHome) => self.index = 0,
+			Some(Input::KeyEnd) => self.index = self.last,
+			Some(Input::Character('q')) | Some(Input::Character('\x1b')) => return false,
+			_ => {}
+		}
+		true
+	}
+
+	fn forward(&mut self) {
+		if self.index < self.last {
+			self.index += 1;
+		}
+		if self.index == self.last {
+			self.paused = true;
+		}
+	}
+
+	fn progress_bar(&self, width: usize) -> String {
+		let label = format!(" move {} of {}", self.index, self.last);
+		let width = width.saturating_sub(label.len() + 2);
+		let filled = match self.last {
+			0 => width,
+			last => width * self.index / last,
+		};
+		format!(
+			"[{}{}]{}",
+			"#".repeat(filled),
+			"-".repeat(width - filled),
+			label
+		)
+	}
+
+	pub fn play(&mut self, solution: &Solution<State>, window: &Window) {
+		noecho();
+		curs_set(0);
+		window.keypad(true);
+		loop {
+			window.clear();
+			window.printw(format!(
+				"Found solution with {} moves, time complexity: {}, memory complexity: {}\n\n",
+				self.last, solution.time, solution.memory
+			));
+			window.printw(format!(
+				"{:size$}\n",
+				solution.states[self.index],
+				size = solution.size as usize
+			));
+			window.printw(format!(
+				"{}\n",
+				self.progress_bar(window.get_max_x() as usize)
+			));
+			let status = match self.paused {
+				true => "paused",
+				false => "playing",
+			};
+			window.printw(format!(
+				"{}, {} ms per move\n{}",
+				status,
+				self.interval.as_millis(),
+				HELP
+			));
+			window.refresh();
+			// Wait for a key, or only for the interval while playing
+			window.timeout(match self.paused {
+				true => -1,
+				false => self.interval.as_millis() as i32,
+			});
+			if !self.handle(window.getch()) {
+				break;
+			}
+		}
+		window.timeout(-1);
+		curs_set(1);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn keys() {
+		let mut player = Player::new(3);
+		assert!(player.handle(None));
+		assert_eq!(player.index, 1);
+		player.handle(Some(Input::Character(' ')));
+		assert!(player.paused);
+		player.handle(None);
+		assert_eq!(player.index, 1);
+		player.handle(Some(Input::KeyLeft));
+		player.handle(Some(Input::KeyLeft));
+		assert_eq!(player.index, 0);
+		player.handle(Some(Input::KeyEnd));
+		assert_eq!(player.index, 3);
+		player.handle(Some(Input::KeyRight));
+		assert_eq!(player.index, 3);
+		// Resuming at the end starts over
+		player.handle(Some(Input::Character(' ')));
+		assert_eq!(player.index, 0);
+		assert!(!player.paused);
+		player.handle(Some(Input::KeyHome));
+		assert_eq!(player.index, 0);
+		assert!(!player.handle(Some(Input::Character('q'))));
+	}
+
+	#[test]
+	fn speed_and_end() {
+		let mut player = Player::new(2);
+		for _ in 0..20 {
+			player.handle(Some(Input::Character('+')));
+		}
+		assert_eq!(player.interval, MIN_INTERVAL);
+		for _ in 0..20 {
+			player.handle(Some(Input::Character('-')));
+		}
+		assert_eq!(player.interval, MAX_INTERVAL);
+		player.handle(None);
+		player.handle(None);
+		assert_eq!(player.index, 2);
+		assert!(player.paused);
+	}
+
+	#[test]
+	fn progress_bar() {
+		let mut player = Player::new(4);
+		player.index = 2;
+		assert_eq!(player.progress_bar(30), "[########--------] move 2 of 4");
+		assert_eq!(Player::new(0).progress_bar(20), "[######] move 0 of 0");
+	}
+}
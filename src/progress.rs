@@ -0,0 +1,146 @@
+use crate::map::Map;
+use crate::state::State;
+use pancurses::Window;
+use std::time::Duration;
+
+// Snapshot of a running search, sent about once per second
+pub struct Progress<'a> {
+	pub iteration: usize,
+	// Number of distinct states currently stored
+	pub distinct: usize,
+	// Lowest score seen so far, if the search uses the heuristic
+	pub best_score: Option<u16>,
+	// Next state to be opened, and the number of moves to reach it
+	pub front: &'a State,
+	pub moves: u16,
+	pub size: u16,
+	// Current bound on moves + score of IDA*
+	pub threshold: Option<u16>,
+}
+
+pub trait ProgressObserver {
+	fn report(&mut self, progress: &Progress);
+
+	// Minimum time between two reports
+	fn interval(&self) -> Duration {
+		Duration::from_secs(1)
+	}
+}
+
+pub struct CursesObserver<'a> {
+	window: &'a Window,
+}
+
+impl<'a> CursesObserver<'a> {
+	pub fn new(window: &'a Window) -> Self {
+		Self { window }
+	}
+}
+
+impl ProgressObserver for CursesObserver<'_> {
+	fn report(&mut self, progress: &Progress) {
+		self.window.clear();
+		self.window
+			.printw(format!("{}\n", StderrObserver::line(progress)));
+		self.window.printw(format!(
+			"{}",
+			Map {
+				size: progress.size,
+				board: progress.front.board.clone()
+			}
+		));
+		self.window.refresh();
+	}
+}
+
+pub struct StderrObserver;
+
+impl StderrObserver {
+	fn line(progress: &Progress) -> String {
+		let score = match progress.best_score {
+			Some(score) => format!("{:3}", score),
+			None => "  -".to_string(),
+		};
+		let line = format!(
+			"Distinct: {:9}, Iteration: {:9}, Score: {}, Moves: {}",
+			progress.distinct, progress.iteration, score, progress.moves
+		);
+		match progress.threshold {
+			Some(threshold) => format!("Threshold: {:3}, {}", threshold, line),
+			None => line,
+		}
+	}
+}
+
+impl ProgressObserver for StderrObserver {
+	fn report(&mut self, progress: &Progress) {
+		eprintln!("{}", Self::line(progress));
+	}
+}
+
+pub struct NoopObserver;
+
+impl ProgressObserver for NoopObserver {
+	fn report(&mut self, _: &Progress) {}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::heuristic::{Manhatthan, HRST};
+	use crate::map::gen_solved_map;
+	use crate::node::LinearPriority;
+	use crate::solver::{Limits, Outcome, Solver};
+
+	// Keeps the iteration and threshold of every report
+	#[derive(Default)]
+	struct Recorder {
+		reports: Vec<(usize, Option<u16>)>,
+	}
+
+	impl ProgressObserver for Recorder {
+		fn report(&mut self, progress: &Progress) {
+			assert_eq!(progress.front.board.len(), 9);
+			self.reports.push((progress.iteration, progress.threshold));
+		}
+
+		fn interval(&self) -> Duration {
+			Duration::ZERO
+		}
+	}
+
+	fn solver() -> Solver {
+		let solved = gen_solved_map(3);
+		let heuristic = HRST::Manhatthan(Manhatthan::new(&solved, 3));
+		Solver::new(&solved, heuristic, Limits::default())
+	}
+
+	fn map() -> Map {
+		Map {
+			size: 3,
+			board: vec![1, 2, 3, 0, 8, 4, 7, 6, 5],
+		}
+	}
+
+	#[test]
+	fn reports_a_star() {
+		let mut recorder = Recorder::default();
+		let outcome = solver().solve::<LinearPriority>(map(), &mut recorder);
+		assert!(matches!(outcome, Outcome::Solved(_)));
+		assert!(!recorder.reports.is_empty());
+		assert!(recorder.reports.iter().all(|(_, t)| t.is_none()));
+		assert!(recorder.reports.windows(2).all(|r| r[0].0 <= r[1].0));
+	}
+
+	#[test]
+	fn reports_ida() {
+		let mut recorder = Recorder::default();
+		let outcome = solver().solve_ida(map(), &mut recorder);
+		assert!(matches!(outcome, Outcome::Solved(_)));
+		let thresholds: Vec<u16> = recorder.reports.iter().filter_map(|r| r.1).collect();
+		assert_eq!(thresholds.len(), recorder.reports.len());
+		// The bound only grows, from the score of the puzzle
+		assert_eq!(thresholds.first(), Some(&1));
+		assert!(thresholds.windows(2).all(|t| t[0] <= t[1]));
+	}
+}
@@ -0,0 +1,251 @@
+use crate::map::Map;
+use crate::parser;
+use crate::solver::Solution;
+use crate::state::{format_moves, parse_moves, State};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// Replays start with MAGIC followed by the format version. Files without it
+// are read as the bincode of a Solution<State>, the original replay format.
+// Read as the length of a Solution's states, MAGIC would be absurdly large.
+const MAGIC: &[u8; 8] = b"NPUZZLE\0";
+const VERSION: u16 = 1;
+const GZIP_MAGIC: &[u8; 2] = b"\x1f\x8b";
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct Metadata {
+	pub heuristic: Option<String>,
+	pub search: Option<String>,
+	// Wall clock time of the search
+	pub duration: Option<Duration>,
+	// Seed of the generator, if the puzzle was generated
+	pub seed: Option<u64>,
+}
+
+pub struct Replay {
+	pub metadata: Metadata,
+	pub solution: Solution<State>,
+}
+
+// Version 1 body, the states are rebuilt from the initial map and the moves
+#[derive(Serialize, Deserialize)]
+struct Body {
+	metadata: Metadata,
+	map: Vec<u16>,
+	size: u16,
+	moves: String,
+	time: usize,
+	memory: usize,
+}
+
+impl Replay {
+	pub fn new(solution: Solution<State>, metadata: Metadata) -> Self {
+		Self { metadata, solution }
+	}
+
+	pub fn write(&self, mut out: impl Write) -> Result<(), Box<dyn Error>> {
+		let body = Body {
+			metadata: self.metadata.clone(),
+			map: self.solution.states[0].board.clone(),
+			size: self.solution.size,
+			moves: format_moves(&self.solution.moves()?),
+			time: self.solution.time,
+			memory: self.solution.memory,
+		};
+		out.write_all(MAGIC)?;
+		out.write_all(&VERSION.to_le_bytes())?;
+		bincode::serialize_into(out, &body)?;
+		Ok(())
+	}
+
+	// Gzip compressed when the path ends with .gz
+	pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+		let file = BufWriter::new(File::create(path)?);
+		if path.extension() == Some(OsStr::new("gz")) {
+			let mut encoder = GzEncoder::new(file, Compression::default());
+			self.write(&mut encoder)?;
+			encoder.finish()?.flush()?;
+		} else {
+			let mut file = file;
+			self.write(&mut file)?;
+			file.flush()?;
+		}
+		Ok(())
+	}
+
+	pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+		Self::read(File::open(path)?)
+	}
+
+	// Gzip compressed input is detected and decompressed
+	pub fn read(mut input: impl Read) -> Result<Self, Box<dyn Error>> {
+		let mut bytes = Vec::new();
+		input.read_to_end(&mut bytes)?;
+		if bytes.starts_with(GZIP_MAGIC) {
+			let mut decoded = Vec::new();
+			GzDecoder::new(&bytes[..]).read_to_end(&mut decoded)?;
+			bytes = decoded;
+		}
+
+		if !bytes.starts_with(MAGIC) {
+			let mut solution: Solution<State> = bincode::deserialize(&bytes)?;
+			if solution.states.is_empty() {
+				return Err("Replay has no states".into());
+			}
+			// The stored blank positions are not trusted
+			let size = solution.size;
+			solution.states = solution
+				.states
+				.into_iter()
+				.map(|state| check_board(size, state.board).map(State::from))
+				.collect::<Result<_, _>>()?;
+			return Ok(Self::new(solution, Metadata::default()));
+		}
+
+		let bytes = &bytes[MAGIC.len()..];
+		if bytes.len() < 2 {
+			return Err("Truncated replay file".into());
+		}
+		let version = u16::from_le_bytes([bytes[0], bytes[1]]);
+		if version != VERSION {
+			return Err(format!("Unsupported replay version {}", version).into());
+		}
+
+		let body: Body = bincode::deserialize(&bytes[2..])?;
+		let start = State::from(check_board(body.size, body.map)?);
+		let moves = parse_moves(&body.moves)?;
+		let solution = Solution::from_moves(start, &moves, body.size, body.time, body.memory)?;
+		Ok(Self::new(solution, body.metadata))
+	}
+}
+
+fn check_board(size: u16, board: Vec<u16>) -> Result<Map, String> {
+	parser::check_board(size, board)
+		.map_err(|e| format!("Invalid board in replay: {}", e.message()))
+}
+
+// Add the .replay extension when there is none, and .gz when compressing
+pub fn save_path(mut path: PathBuf, compress: bool) -> PathBuf {
+	if path.extension().is_none() {
+		path.set_extension("replay");
+	}
+	if compress && path.extension() != Some(OsStr::new("gz")) {
+		let mut name = path.into_os_string();
+		name.push(".gz");
+		path = PathBuf::from(name);
+	}
+	path
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn read_legacy() {
+		let replay = Replay::read(&include_bytes!("../maps/3x3.replay")[..]).unwrap();
+		assert_eq!(replay.solution.size, 3);
+		assert!(replay.metadata.heuristic.is_none());
+		let replay = Replay::read(&include_bytes!("../maps/4x4.replay")[..]).unwrap();
+		assert_eq!(replay.solution.size, 4);
+	}
+
+	#[test]
+	fn roundtrip() {
+		let legacy = Replay::read(&include_bytes!("../maps/3x3.replay")[..]).unwrap();
+		let metadata = Metadata {
+			heuristic: Some("linear-conflict".to_string()),
+			search: Some("ida".to_string()),
+			duration: Some(Duration::from_millis(12)),
+			seed: Some(42),
+		};
+		let mut bytes = Vec::new();
+		Replay::new(legacy.solution, metadata)
+			.write(&mut bytes)
+			.unwrap();
+		assert!(bytes.starts_with(MAGIC));
+
+		let replay = Replay::read(&bytes[..]).unwrap();
+		let legacy = Replay::read(&include_bytes!("../maps/3x3.replay")[..]).unwrap();
+		assert!(replay.solution.states == legacy.solution.states);
+		assert_eq!(replay.solution.time, legacy.solution.time);
+		assert_eq!(replay.metadata.seed, Some(42));
+		assert_eq!(replay.metadata.search.as_deref(), Some("ida"));
+	}
+
+	#[test]
+	fn read_legacy_empty() {
+		let solution = Solution::<State> {
+			states: Vec::new(),
+			size: 3,
+			time: 0,
+			memory: 0,
+		};
+		let bytes = bincode::serialize(&solution).unwrap();
+		let e = Replay::read(&bytes[..]).err().unwrap();
+		assert_eq!(e.to_string(), "Replay has no states");
+	}
+
+	#[test]
+	fn read_invalid_board() {
+		let body = Body {
+			metadata: Metadata::default(),
+			map: vec![1, 2, 3],
+			size: 3,
+			moves: String::new(),
+			time: 0,
+			memory: 0,
+		};
+		let mut bytes = MAGIC.to_vec();
+		bytes.extend_from_slice(&VERSION.to_le_bytes());
+		bincode::serialize_into(&mut bytes, &body).unwrap();
+		let e = Replay::read(&bytes[..]).err().unwrap();
+		assert!(e.to_string().starts_with("Invalid board in replay: "));
+	}
+
+	#[test]
+	fn read_gzip() {
+		let replay = Replay::read(&include_bytes!("../maps/5x5.replay.gz")[..]).unwrap();
+		assert_eq!(replay.solution.size, 5);
+	}
+
+	#[test]
+	fn save_gzip() {
+		let path = std::env::temp_dir().join(format!("n-puzzle-{}.replay.gz", std::process::id()));
+		let legacy = Replay::read(&include_bytes!("../maps/3x3.replay")[..]).unwrap();
+		Replay::new(legacy.solution, Metadata::default())
+			.save(&path)
+			.unwrap();
+		let bytes = std::fs::read(&path).unwrap();
+		assert!(bytes.starts_with(GZIP_MAGIC));
+		let replay = Replay::load(&path).unwrap();
+		std::fs::remove_file(&path).unwrap();
+		assert_eq!(replay.solution.states.len(), 25);
+	}
+
+	#[test]
+	fn save_paths() {
+		let path = |p: &str, compress| save_path(PathBuf::from(p), compress);
+		assert_eq!(path("out", false), PathBuf::from("out.replay"));
+		assert_eq!(path("out", true), PathBuf::from("out.replay.gz"));
+		assert_eq!(path("out.bin", false), PathBuf::from("out.bin"));
+		assert_eq!(path("out.replay", true), PathBuf::from("out.replay.gz"));
+		assert_eq!(path("out.replay.gz", false), PathBuf::from("out.replay.gz"));
+		assert_eq!(path("out.replay.gz", true), PathBuf::from("out.replay.gz"));
+	}
+
+	#[test]
+	fn unsupported_version() {
+		let mut bytes = MAGIC.to_vec();
+		bytes.extend_from_slice(&2u16.to_le_bytes());
+		assert!(Replay::read(&bytes[..]).is_err());
+	}
+}
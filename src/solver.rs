@@ -1,20 +1,79 @@
-use ahash::AHashSet;
+use ahash::{AHashMap, AHashSet};
 use pancurses::Window;
 use serde::{Deserialize, Serialize};
-use std::cmp::min;
 use std::collections::BinaryHeap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io::Write;
 use std::rc::Rc;
-use std::thread;
 use std::time::{Duration, Instant};
 
 use crate::heuristic::{Heuristic, HRST};
 use crate::map::{gen_solved_map, Map};
 use crate::node::{Node, Priority};
+use crate::player::Player;
+use crate::progress::{Progress, ProgressObserver};
+use crate::replay::Metadata;
 use crate::state::Point;
-use crate::state::State;
+use crate::state::{format_moves, Move, State};
 
 pub struct Solver {
 	heuristic: HRST,
+	limits: Limits,
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct Limits {
+	pub time: Option<Duration>,
+	// Number of states opened
+	pub nodes: Option<usize>,
+	// Estimated bytes used by the stored states
+	pub memory: Option<usize>,
+}
+
+// Rough cost of a stored state besides its board: the Rc and Vec headers,
+// the node, and the set and queue entries
+const STATE_OVERHEAD: usize = 96;
+
+impl Limits {
+	fn check(&self, start: Instant, nodes: usize, stored: usize, size: u16) -> Option<Limit> {
+		let memory = stored * ((size as usize).pow(2) * 2 + STATE_OVERHEAD);
+		if self.time.is_some_and(|time| start.elapsed() >= time) {
+			Some(Limit::Time)
+		} else if self.nodes.is_some_and(|max| nodes >= max) {
+			Some(Limit::Nodes)
+		} else if self.memory.is_some_and(|max| memory >= max) {
+			Some(Limit::Memory)
+		} else {
+			None
+		}
+	}
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Limit {
+	Time,
+	Nodes,
+	Memory,
+}
+
+impl Display for Limit {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Limit::Time => f.write_str("Time limit"),
+			Limit::Nodes => f.write_str("Node limit"),
+			Limit::Memory => f.write_str("Memory limit"),
+		}
+	}
+}
+
+pub enum Outcome {
+	Solved(Solution<Rc<State>>),
+	// The search was stopped, the solution leads to the best state found
+	LimitReached(Limit, Solution<Rc<State>>),
+	// Every reachable state was opened without finding the goal
+	Exhausted { time: usize, memory: usize },
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -43,7 +102,7 @@ impl From<Solution<Rc<State>>> for Solution<State> {
 }
 
 impl Solver {
-	pub fn new(solved_map: &Map, heuristic: HRST) -> Self {
+	pub fn new(solved_map: &Map, heuristic: HRST, limits: Limits) -> Self {
 		let mut solved_table: Vec<Point> =
 			vec![Point::from_1d(0, solved_map.size); solved_map.board.len()];
 
@@ -52,12 +111,16 @@ impl Solver {
 			solved_table[*item as usize] = p;
 		}
 
-		Self { heuristic }
+		Self { heuristic, limits }
+	}
+
+	pub fn is_admissible(&self) -> bool {
+		self.heuristic.is_admissible()
 	}
 }
 
 impl Solver {
-	fn get_inv_count(board: &Vec<u16>) -> u16 {
+	fn get_inv_count(board: &[u16]) -> u16 {
 		let mut count: u16 = 0;
 
 		for i in 0..(board.len() - 1) {
@@ -65,7 +128,7 @@ impl Solver {
 				let left = board[i];
 				let right = board[j];
 
-				if  right > left && right != 0 && left != 0 {
+				if right > left && right != 0 && left != 0 {
 					count += 1;
 				}
 			}
@@ -74,12 +137,12 @@ impl Solver {
 		count
 	}
 
-	pub fn is_solvable(&self, map: &Map) -> bool {
+	pub fn is_solvable(map: &Map) -> bool {
 		let solved_map = gen_solved_map(map.size as usize);
 		let mut inv_count = Self::get_inv_count(&map.board);
 		let mut solved_inv_count = Self::get_inv_count(&solved_map.board);
 
-		if map.size.is_even() == true {
+		if map.size.is_even() {
 			let zero = map.board.iter().position(|&r| r == 0).unwrap() as u16;
 			let solved_zero = solved_map.board.iter().position(|&r| r == 0).unwrap() as u16;
 
@@ -90,7 +153,12 @@ impl Solver {
 		inv_count.is_even() == solved_inv_count.is_even()
 	}
 
-	pub fn solve<P: Priority + Ord>(&self, map: Map, window: &Window) -> Solution<Rc<State>> {
+	pub fn solve<P: Priority + Ord>(
+		&self,
+		map: Map,
+		observer: &mut dyn ProgressObserver,
+	) -> Outcome {
+		let start = Instant::now();
 		let size = map.size;
 		let root = Rc::new(State::from(map));
 
@@ -110,107 +178,487 @@ impl Solver {
 		states_set.insert(root);
 
 		let mut last_print = Instant::now();
+		let mut best_index: usize = 0;
 
 		let mut i: usize = 0;
 		loop {
-			let node_index = queue.pop().unwrap().get_index();
+			let node_index = match queue.pop() {
+				Some(priority) => priority.get_index(),
+				None => {
+					return Outcome::Exhausted {
+						time: i,
+						memory: states_set.len(),
+					}
+				}
+			};
+			if let Some(limit) = self.limits.check(start, i, states_set.len(), size) {
+				return Outcome::LimitReached(
+					limit,
+					Solution {
+						states: nodes[best_index].collect_parents(&nodes),
+						size,
+						time: i,
+						memory: states_set.len(),
+					},
+				);
+			}
 			let state = nodes[node_index].state.clone();
 			let moves = nodes[node_index].moves;
 
-			if Instant::now().duration_since(last_print) > Duration::from_secs(1) {
+			if last_print.elapsed() >= observer.interval() {
 				last_print = Instant::now();
-				window.clear();
-				window.printw(format!(
-					"Distinct: {:9}, Iteration: {:9}, Score: {:3}, Moves: {}\n",
-					states_set.len(),
-					i,
-					best_score,
-					moves
-				));
-				let index = queue.peek().unwrap().get_index();
-				window.printw(format!(
-					"{}",
-					Map {
-						size,
-						board: nodes[index].state.board.clone()
+				let index = queue.peek().map_or(node_index, |p| p.get_index());
+				observer.report(&Progress {
+					iteration: i,
+					distinct: states_set.len(),
+					best_score: Some(best_score),
+					front: &nodes[index].state,
+					moves: nodes[index].moves,
+					size,
+					threshold: None,
+				});
+			}
+
+			for state in IntoIterator::into_iter(state.gen_children(size)).flatten() {
+				let state = Rc::new(state);
+				i += 1;
+				if states_set.insert(state.clone()) {
+					let score = self.heuristic.compute_score(&state);
+					let new_node = Node {
+						parent: Some(node_index),
+						state,
+						moves: moves + 1,
+					};
+					if score == 0 {
+						return Outcome::Solved(Solution {
+							states: new_node.collect_parents(&nodes),
+							size,
+							time: i,
+							memory: states_set.len(),
+						});
 					}
-				));
-				window.refresh();
+					queue.push(P::new(nodes.len(), score, new_node.moves));
+					if score < best_score {
+						best_score = score;
+						best_index = nodes.len();
+					}
+					nodes.push(new_node);
+				}
 			}
+		}
+	}
+}
+
+// One side of a bidirectional search, expanded one breadth first layer at a time
+struct Frontier {
+	nodes: Vec<Node>,
+	seen: AHashMap<Rc<State>, usize>,
+	layer: Vec<usize>,
+}
+
+impl Frontier {
+	fn new(root: State) -> Self {
+		let root = Rc::new(root);
+		let mut seen = AHashMap::new();
+		seen.insert(root.clone(), 0);
 
-			for child in state.gen_children(size) {
-				if let Some(state) = child {
-					let state = Rc::new(state);
-					i += 1;
-					if states_set.insert(state.clone()) {
-						let score = self.heuristic.compute_score(&state);
-						let new_node = Node {
-							parent: Some(node_index),
-							state,
-							moves: moves + 1,
-						};
-						if score == 0 {
-							return Solution {
-								states: new_node.collect_parents(&nodes),
+		Self {
+			nodes: vec![Node {
+				parent: None,
+				state: root,
+				moves: 0,
+			}],
+			seen,
+			layer: vec![0],
+		}
+	}
+
+	// Expand the whole current layer and return a junction with the other side
+	// found on the way, as (index in self, index in other)
+	fn expand(
+		&mut self,
+		other: &Frontier,
+		size: u16,
+		generated: &mut usize,
+		limits: &Limits,
+		start: Instant,
+	) -> Result<Option<(usize, usize)>, Limit> {
+		let mut next = Vec::new();
+		let mut junction: Option<(usize, usize)> = None;
+
+		for &index in self.layer.iter() {
+			let stored = self.seen.len() + other.seen.len();
+			if let Some(limit) = limits.check(start, *generated, stored, size) {
+				return Err(limit);
+			}
+			let state = self.nodes[index].state.clone();
+			let moves = self.nodes[index].moves + 1;
+			for child in IntoIterator::into_iter(state.gen_children(size)).flatten() {
+				*generated += 1;
+				if self.seen.contains_key(&child) {
+					continue;
+				}
+				let child = Rc::new(child);
+				let child_index = self.nodes.len();
+				if let Some(&other_index) = other.seen.get(&child) {
+					junction.get_or_insert((child_index, other_index));
+				}
+				self.seen.insert(child.clone(), child_index);
+				self.nodes.push(Node {
+					parent: Some(index),
+					state: child,
+					moves,
+				});
+				next.push(child_index);
+			}
+		}
+		self.layer = next;
+		Ok(junction)
+	}
+}
+
+impl Solver {
+	// Breadth first search from both the start and the goal, always expanding
+	// the smaller frontier. A layer is expanded completely before stopping, so
+	// every junction found then has the same, shortest, length.
+	// The heuristic is not used.
+	pub fn solve_bidirectional(&self, map: Map, observer: &mut dyn ProgressObserver) -> Outcome {
+		let start = Instant::now();
+		let size = map.size;
+		let mut forward = Frontier::new(State::from(map));
+		let mut backward = Frontier::new(State::from(gen_solved_map(size as usize)));
+		let mut generated: usize = 0;
+		let mut last_print = Instant::now();
+
+		let junction = if forward.seen.contains_key(&backward.nodes[0].state) {
+			Some((0, 0))
+		} else {
+			None
+		};
+		let (forward_index, backward_index) = match junction {
+			Some(junction) => junction,
+			None => loop {
+				if forward.layer.is_empty() || backward.layer.is_empty() {
+					return Outcome::Exhausted {
+						time: generated,
+						memory: forward.seen.len() + backward.seen.len(),
+					};
+				}
+				let junction = if forward.layer.len() <= backward.layer.len() {
+					forward.expand(&backward, size, &mut generated, &self.limits, start)
+				} else {
+					backward
+						.expand(&forward, size, &mut generated, &self.limits, start)
+						.map(|junction| junction.map(|(b, f)| (f, b)))
+				};
+				match junction {
+					Ok(Some(junction)) => break junction,
+					Ok(None) => {}
+					// Without a heuristic, the deepest forward state is as good as any
+					Err(limit) => {
+						let last = forward.nodes.last().unwrap();
+						return Outcome::LimitReached(
+							limit,
+							Solution {
+								states: last.collect_parents(&forward.nodes),
 								size,
-								time: i,
-								memory: states_set.len(),
-							};
-						}
-						queue.push(P::new(nodes.len(), score, new_node.moves));
-						if score < best_score {
-							best_score = score;
-						}
-						nodes.push(new_node);
+								time: generated,
+								memory: forward.seen.len() + backward.seen.len(),
+							},
+						);
 					}
 				}
+
+				if last_print.elapsed() >= observer.interval() {
+					last_print = Instant::now();
+					let front = forward.nodes.last().unwrap();
+					observer.report(&Progress {
+						iteration: generated,
+						distinct: forward.seen.len() + backward.seen.len(),
+						best_score: None,
+						front: &front.state,
+						moves: front.moves,
+						size,
+						threshold: None,
+					});
+				}
+			},
+		};
+
+		let mut states = forward.nodes[forward_index].collect_parents(&forward.nodes);
+		let mut to_goal = backward.nodes[backward_index].collect_parents(&backward.nodes);
+		to_goal.reverse();
+		states.extend(to_goal.into_iter().skip(1));
+
+		Outcome::Solved(Solution {
+			states,
+			size,
+			time: generated,
+			memory: forward.seen.len() + backward.seen.len(),
+		})
+	}
+}
+
+enum Bound {
+	Found,
+	Exceeded(u16),
+	Stopped(Limit),
+}
+
+// State of an iterative deepening search, shared by the recursive calls
+struct IdaSearch {
+	path: Vec<State>,
+	// path to the state with the lowest score seen
+	best: Vec<State>,
+	best_score: u16,
+	expanded: usize,
+	max_depth: usize,
+	start: Instant,
+	last_print: Instant,
+}
+
+impl Solver {
+	// Iterative deepening A*: depth first searches bounded by moves + score,
+	// the bound being raised to the smallest exceeding value after each pass.
+	// Only the current path is kept in memory.
+	pub fn solve_ida(&self, map: Map, observer: &mut dyn ProgressObserver) -> Outcome {
+		let size = map.size;
+		let root = State::from(map);
+		let mut threshold = self.heuristic.compute_score(&root);
+		let mut search = IdaSearch {
+			path: vec![root.clone()],
+			best: vec![root],
+			best_score: threshold,
+			expanded: 0,
+			max_depth: 1,
+			start: Instant::now(),
+			last_print: Instant::now(),
+		};
+
+		loop {
+			let (states, limit) = match self.ida_search(&mut search, 0, threshold, size, observer) {
+				Bound::Exceeded(u16::MAX) => {
+					return Outcome::Exhausted {
+						time: search.expanded,
+						memory: search.max_depth,
+					}
+				}
+				Bound::Exceeded(next) => {
+					threshold = next;
+					continue;
+				}
+				Bound::Found => (search.path, None),
+				Bound::Stopped(limit) => (search.best, Some(limit)),
+			};
+			let solution = Solution {
+				states: states.into_iter().map(Rc::new).collect(),
+				size,
+				time: search.expanded,
+				memory: search.max_depth,
+			};
+			return match limit {
+				Some(limit) => Outcome::LimitReached(limit, solution),
+				None => Outcome::Solved(solution),
+			};
+		}
+	}
+
+	fn ida_search(
+		&self,
+		search: &mut IdaSearch,
+		moves: u16,
+		threshold: u16,
+		size: u16,
+		observer: &mut dyn ProgressObserver,
+	) -> Bound {
+		let state = search.path.last().unwrap();
+		let score = self.heuristic.compute_score(state);
+		if score == 0 {
+			return Bound::Found;
+		}
+		if score < search.best_score {
+			search.best_score = score;
+			search.best = search.path.clone();
+		}
+		let limit = self
+			.limits
+			.check(search.start, search.expanded, search.path.len(), size);
+		if let Some(limit) = limit {
+			return Bound::Stopped(limit);
+		}
+		if moves + score > threshold {
+			return Bound::Exceeded(moves + score);
+		}
+
+		if search.last_print.elapsed() >= observer.interval() {
+			search.last_print = Instant::now();
+			observer.report(&Progress {
+				iteration: search.expanded,
+				distinct: search.path.len(),
+				best_score: Some(search.best_score),
+				front: state,
+				moves,
+				size,
+				threshold: Some(threshold),
+			});
+		}
+
+		let parent = search
+			.path
+			.len()
+			.checked_sub(2)
+			.map(|i| search.path[i].clone());
+		let mut min = u16::MAX;
+		for child in IntoIterator::into_iter(state.gen_children(size)).flatten() {
+			if Some(&child) == parent.as_ref() {
+				continue;
+			}
+			search.expanded += 1;
+			search.path.push(child);
+			search.max_depth = search.max_depth.max(search.path.len());
+			match self.ida_search(search, moves + 1, threshold, size, observer) {
+				Bound::Exceeded(bound) => min = min.min(bound),
+				bound => return bound,
 			}
+			search.path.pop();
 		}
+		Bound::Exceeded(min)
 	}
 }
 
 trait Oddness {
-	fn is_odd(&self) -> bool;
 	fn is_even(&self) -> bool;
 }
 
 impl Oddness for std::primitive::u16 {
-	#[inline]
-	fn is_odd(&self) -> bool {
-		self & 1 == 1
-	}
-
 	#[inline]
 	fn is_even(&self) -> bool {
 		self & 1 == 0
 	}
 }
 
+// Result of a search for --format json, the moves and states are only given
+// when the puzzle is solved
+#[derive(Serialize)]
+pub struct JsonReport<'a> {
+	// solved, unsolvable, limit_reached or exhausted
+	pub status: &'static str,
+	pub limit: Option<Limit>,
+	pub heuristic: Option<&'a str>,
+	pub search: Option<&'a str>,
+	// Wall clock time of the search, in seconds
+	pub duration: Option<f64>,
+	pub seed: Option<u64>,
+	pub size: u16,
+	pub moves: Option<Vec<Move>>,
+	pub states: Option<Vec<&'a [u16]>>,
+	pub time: Option<usize>,
+	pub memory: Option<usize>,
+}
+
+impl<'a> JsonReport<'a> {
+	pub fn new(status: &'static str, size: u16, metadata: &'a Metadata) -> Self {
+		Self {
+			status,
+			limit: None,
+			heuristic: metadata.heuristic.as_deref(),
+			search: metadata.search.as_deref(),
+			duration: metadata.duration.map(|d| d.as_secs_f64()),
+			seed: metadata.seed,
+			size,
+			moves: None,
+			states: None,
+			time: None,
+			memory: None,
+		}
+	}
+
+	pub fn write(&self, out: &mut impl Write) -> serde_json::Result<()> {
+		serde_json::to_writer(&mut *out, self)?;
+		writeln!(out).map_err(serde_json::Error::io)
+	}
+}
+
 impl Solution<State> {
+	// Interactive replay of the states, see Player for the keys
 	pub fn print(&self, window: &Window) {
-		// TODO: Skip, Display very large
-		let interval = min(
-			Duration::from_secs(20) / self.states.len() as u32,
-			Duration::from_millis(250),
-		);
-		let mut last_print;
-		for state in self.states.iter() {
-			last_print = Instant::now();
-			window.clear();
-			window.printw(format!(
-				"Found solution with {} moves, time complexity: {}, memory complexity: {}\n\n",
-				self.states.len(),
-				self.time,
-				self.memory
-			));
-			window.printw(format!("{:size$}", state, size = self.size as usize));
-			window.refresh();
-			if interval > last_print.elapsed() {
-				thread::sleep(interval - last_print.elapsed());
+		Player::new(self.states.len() - 1).play(self, window);
+	}
+
+	// Rebuild the states from the initial one and the moves of the blank
+	pub fn from_moves(
+		start: State,
+		moves: &[Move],
+		size: u16,
+		time: usize,
+		memory: usize,
+	) -> Result<Self, String> {
+		let mut states = vec![start];
+		for (i, m) in moves.iter().enumerate() {
+			match states.last().unwrap().apply(*m, size) {
+				Some(state) => states.push(state),
+				None => return Err(format!("Move {} ({:?}) leaves the board", i + 1, m)),
+			}
+		}
+		Ok(Self {
+			states,
+			size,
+			time,
+			memory,
+		})
+	}
+
+	// Fails on two states that are not one move of the blank apart, the blanks
+	// being adjacent and the other tiles in place, so that a corrupt solution
+	// is not shortened
+	pub fn moves(&self) -> Result<Vec<Move>, String> {
+		self.states
+			.windows(2)
+			.enumerate()
+			.map(|(i, pair)| {
+				let m = pair[0].move_to(&pair[1]);
+				m.filter(|&m| pair[0].apply(m, self.size).as_ref() == Some(&pair[1]))
+					.ok_or_else(|| {
+						format!(
+							"Step {}: the states are not one move of the blank apart",
+							i + 1
+						)
+					})
+			})
+			.collect()
+	}
+
+	// Metadata fields are null when unknown, as for legacy replays or given moves
+	pub fn print_json(
+		&self,
+		out: &mut impl Write,
+		metadata: &Metadata,
+	) -> Result<(), Box<dyn Error>> {
+		JsonReport {
+			moves: Some(self.moves()?),
+			states: Some(self.states.iter().map(|s| s.board.as_slice()).collect()),
+			time: Some(self.time),
+			memory: Some(self.memory),
+			..JsonReport::new("solved", self.size, metadata)
+		}
+		.write(out)?;
+		Ok(())
+	}
+
+	// Plain text version of print, for use without a terminal
+	pub fn print_text(&self, out: &mut impl Write, states: bool) -> Result<(), Box<dyn Error>> {
+		let moves = self.moves()?;
+		writeln!(out, "Moves: {}", self.states.len() - 1)?;
+		writeln!(out, "Time complexity: {}", self.time)?;
+		writeln!(out, "Memory complexity: {}", self.memory)?;
+		writeln!(out, "Solution: {}", format_moves(&moves))?;
+		// With use_move, solutions are only shown as moves
+		if states && cfg!(not(feature = "use_move")) {
+			for state in self.states.iter() {
+				write!(out, "\n{:size$}", state, size = self.size as usize)?;
 			}
 		}
-		window.mvprintw(window.get_max_y() - 1, 0, "Press any key to continue...");
-		window.getch();
+		Ok(())
 	}
 }
 
@@ -259,3 +707,134 @@ impl Solution<State> {
 // 		}
 // 	}
 // }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::heuristic::{parse_heuristic, LinearConflict, Manhatthan};
+	use crate::node::{LinearPriority, UniformPriority};
+	use crate::progress::NoopObserver;
+	use crate::replay::Replay;
+
+	fn solver(heuristic: HRST) -> Solver {
+		Solver::new(&gen_solved_map(3), heuristic, Limits::default())
+	}
+
+	fn map() -> Map {
+		Map {
+			size: 3,
+			board: vec![3, 2, 8, 4, 1, 5, 6, 7, 0],
+		}
+	}
+
+	fn moves(outcome: Outcome) -> usize {
+		match outcome {
+			Outcome::Solved(solution) => {
+				let solution = Solution::<State>::from(solution);
+				assert_eq!(
+					solution.states.last().unwrap().board,
+					gen_solved_map(3).board
+				);
+				let moves = solution.moves().unwrap();
+				assert_eq!(moves.len(), solution.states.len() - 1);
+				let start = solution.states[0].clone();
+				let rebuilt = Solution::from_moves(start, &moves, 3, 0, 0).unwrap();
+				assert!(rebuilt.states == solution.states);
+				solution.states.len() - 1
+			}
+			_ => panic!("puzzle not solved"),
+		}
+	}
+
+	#[test]
+	fn solve_optimal_modes() {
+		let solved = gen_solved_map(3);
+		let s = solver(HRST::LinearConflict(LinearConflict::new(&solved, 3)));
+		assert_eq!(moves(s.solve_ida(map(), &mut NoopObserver)), 24);
+		assert_eq!(moves(s.solve_bidirectional(map(), &mut NoopObserver)), 24);
+		assert_eq!(
+			moves(s.solve::<UniformPriority>(map(), &mut NoopObserver)),
+			24
+		);
+	}
+
+	#[test]
+	fn moves_not_adjacent() {
+		let start = State::from(map());
+		let solved = State::from(gen_solved_map(3));
+		let solution = Solution {
+			states: vec![
+				start.clone(),
+				start.apply(Move::Up, 3).unwrap(),
+				solved.clone(),
+			],
+			size: 3,
+			time: 0,
+			memory: 0,
+		};
+		assert_eq!(
+			solution.moves(),
+			Err("Step 2: the states are not one move of the blank apart".to_string())
+		);
+		let skipped = Solution {
+			states: vec![start, solved],
+			size: 3,
+			time: 0,
+			memory: 0,
+		};
+		assert!(skipped.moves().unwrap_err().starts_with("Step 1: "));
+		assert!(Replay::new(solution, Metadata::default())
+			.write(Vec::new())
+			.is_err());
+	}
+
+	#[test]
+	fn solve_max_weight() {
+		// The largest weight must not overflow moves + score
+		let solved = gen_solved_map(3);
+		let expr = parse_heuristic("weighted(manhatthan,100)").unwrap();
+		let s = solver(expr.build(&solved).unwrap());
+		assert!(moves(s.solve_ida(map(), &mut NoopObserver)) >= 24);
+		assert!(moves(s.solve::<LinearPriority>(map(), &mut NoopObserver)) >= 24);
+	}
+
+	#[test]
+	fn solve_node_limit() {
+		let solved = gen_solved_map(3);
+		let limits = Limits {
+			nodes: Some(10),
+			..Limits::default()
+		};
+		let s = Solver::new(
+			&solved,
+			HRST::Manhatthan(Manhatthan::new(&solved, 3)),
+			limits,
+		);
+		assert!(matches!(
+			s.solve::<LinearPriority>(map(), &mut NoopObserver),
+			Outcome::LimitReached(Limit::Nodes, _)
+		));
+		assert!(matches!(
+			s.solve_ida(map(), &mut NoopObserver),
+			Outcome::LimitReached(Limit::Nodes, _)
+		));
+	}
+
+	#[test]
+	fn json_report() {
+		let metadata = Metadata::default();
+		let mut out = Vec::new();
+		JsonReport {
+			limit: Some(Limit::Nodes),
+			time: Some(10),
+			..JsonReport::new("limit_reached", 3, &metadata)
+		}
+		.write(&mut out)
+		.unwrap();
+		let report: serde_json::Value = serde_json::from_slice(&out).unwrap();
+		assert_eq!(report["status"], "limit_reached");
+		assert_eq!(report["limit"], "nodes");
+		assert_eq!(report["time"], 10);
+		assert!(report["moves"].is_null());
+	}
+}
@@ -73,6 +73,52 @@ impl Point {
 	}
 }
 
+// Direction in which the blank moves
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Move {
+	Up,
+	Down,
+	Left,
+	Right,
+}
+
+impl Move {
+	pub fn to_char(self) -> char {
+		match self {
+			Move::Up => 'U',
+			Move::Down => 'D',
+			Move::Left => 'L',
+			Move::Right => 'R',
+		}
+	}
+
+	pub fn from_char(c: char) -> Option<Move> {
+		match c.to_ascii_uppercase() {
+			'U' => Some(Move::Up),
+			'D' => Some(Move::Down),
+			'L' => Some(Move::Left),
+			'R' => Some(Move::Right),
+			_ => None,
+		}
+	}
+}
+
+// Compact representation of a solution, such as `RRDLU`
+pub fn format_moves(moves: &[Move]) -> String {
+	moves.iter().map(|m| m.to_char()).collect()
+}
+
+pub fn parse_moves(input: &str) -> Result<Vec<Move>, String> {
+	input
+		.chars()
+		.filter(|c| !c.is_whitespace())
+		.enumerate()
+		.map(|(i, c)| {
+			Move::from_char(c).ok_or(format!("Invalid move `{}` at position {}", c, i + 1))
+		})
+		.collect()
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct State {
 	// state of the board
@@ -110,6 +156,33 @@ impl State {
 		State::new(v, new_pos)
 	}
 
+	// Move of the blank leading from self to other, if the blanks are adjacent
+	pub fn move_to(&self, other: &State) -> Option<Move> {
+		let (from, to) = (&self.zero, &other.zero);
+		if from.y == to.y && from.x == to.x + 1 {
+			Some(Move::Up)
+		} else if from.y == to.y && from.x + 1 == to.x {
+			Some(Move::Down)
+		} else if from.x == to.x && from.y == to.y + 1 {
+			Some(Move::Left)
+		} else if from.x == to.x && from.y + 1 == to.y {
+			Some(Move::Right)
+		} else {
+			None
+		}
+	}
+
+	// State after moving the blank, None if it would leave the board
+	pub fn apply(&self, m: Move, size: u16) -> Option<State> {
+		let new_pos = match m {
+			Move::Up => self.zero.left(),
+			Move::Down => self.zero.right(size),
+			Move::Left => self.zero.up(),
+			Move::Right => self.zero.down(size),
+		};
+		new_pos.map(|p| self.build_child(p, size))
+	}
+
 	pub fn gen_children(&self, size: u16) -> [Option<State>; 4] {
 		let children_pos: [Option<Point>; 4] = [
 			self.zero.left(),
@@ -257,3 +330,34 @@ impl Hash for State {
 // 		let _s2 = s.build_child(&Point { x: 10, y: 10 }, 3);
 // 	}
 // }
+
+#[cfg(test)]
+mod move_tests {
+	use super::*;
+
+	#[test]
+	fn moves_roundtrip() {
+		let moves = parse_moves("RRd lU").unwrap();
+		assert_eq!(
+			moves,
+			vec![Move::Right, Move::Right, Move::Down, Move::Left, Move::Up]
+		);
+		assert_eq!(format_moves(&moves), "RRDLU");
+		assert!(parse_moves("RX").is_err());
+	}
+
+	#[test]
+	fn apply_and_move_to() {
+		let s = State::from(Map {
+			board: vec![1, 2, 3, 8, 0, 4, 7, 6, 5],
+			size: 3,
+		});
+		for m in [Move::Up, Move::Down, Move::Left, Move::Right].iter() {
+			let child = s.apply(*m, 3).unwrap();
+			assert_eq!(s.move_to(&child), Some(*m));
+		}
+		let up = s.apply(Move::Up, 3).unwrap();
+		assert_eq!(up.board, vec![1, 0, 3, 8, 2, 4, 7, 6, 5]);
+		assert!(up.apply(Move::Up, 3).is_none());
+	}
+}
@@ -0,0 +1,195 @@
+use crate::map::gen_solved_map;
+use crate::state::{Move, Point, State};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+// Ways a solution can be wrong, steps are numbered from 1
+#[derive(Debug)]
+pub enum Problem {
+	// The solution does not start from the given puzzle
+	WrongStart { expected: State },
+	// The blank cannot move that way
+	IllegalMove { step: usize, m: Move, from: State },
+	// The two states are not one move of the blank apart
+	IllegalStep { step: usize, from: State, to: State },
+	// The last state is not the goal
+	Unsolved { last: State },
+	Empty,
+}
+
+// First problem found in a solution
+#[derive(Debug)]
+pub struct VerifyError {
+	pub problem: Problem,
+	pub size: u16,
+}
+
+impl Display for VerifyError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let size = self.size as usize;
+		match &self.problem {
+			// The puzzle may not have the size of the solution
+			Problem::WrongStart { expected } => write!(
+				f,
+				"The solution does not start from the puzzle:\n{:size$}",
+				expected,
+				size = (expected.board.len() as f64).sqrt() as usize
+			),
+			Problem::IllegalMove { step, m, from } => write!(
+				f,
+				"Step {}: the blank cannot move {:?} from\n{:size$}",
+				step,
+				m,
+				from,
+				size = size
+			),
+			Problem::IllegalStep { step, from, to } => write!(
+				f,
+				"Step {}: not a single move of the blank from\n{:size$}to\n{:size$}",
+				step,
+				from,
+				to,
+				size = size
+			),
+			Problem::Unsolved { last } => write!(
+				f,
+				"The solution ends before the goal, on\n{:size$}",
+				last,
+				size = size
+			),
+			Problem::Empty => f.write_str("The solution has no state"),
+		}
+	}
+}
+
+impl Error for VerifyError {}
+
+// Checks that each state is a child of the previous one and that the last is
+// the goal, returning the number of moves
+pub fn verify_states(
+	states: &[State],
+	size: u16,
+	start: Option<&State>,
+) -> Result<usize, VerifyError> {
+	let fail = |problem| Err(VerifyError { problem, size });
+	let first = match states.first() {
+		Some(first) => first,
+		None => return fail(Problem::Empty),
+	};
+	if let Some(start) = start {
+		if first != start {
+			return fail(Problem::WrongStart {
+				expected: start.clone(),
+			});
+		}
+	}
+	for (i, pair) in states.windows(2).enumerate() {
+		let (from, to) = (&pair[0], &pair[1]);
+		// The blank is found on the board, the stored position may be wrong
+		let blank = from.board.iter().position(|&t| t == 0);
+		let legal = match blank {
+			Some(blank) if from.board.len() == (size as usize).pow(2) => {
+				let from = State::new(from.board.clone(), Point::from_1d(blank as u16, size));
+				IntoIterator::into_iter(from.gen_children(size))
+					.flatten()
+					.any(|child| child == *to)
+			}
+			_ => false,
+		};
+		if !legal {
+			return fail(Problem::IllegalStep {
+				step: i + 1,
+				from: from.clone(),
+				to: to.clone(),
+			});
+		}
+	}
+	let last = states.last().unwrap();
+	if *last != State::from(gen_solved_map(size as usize)) {
+		return fail(Problem::Unsolved { last: last.clone() });
+	}
+	Ok(states.len() - 1)
+}
+
+// Plays the moves from start, then checks the states
+pub fn verify_moves(start: State, moves: &[Move], size: u16) -> Result<usize, VerifyError> {
+	let mut states = vec![start];
+	for (i, &m) in moves.iter().enumerate() {
+		let from = states.last().unwrap();
+		match from.apply(m, size) {
+			Some(state) => states.push(state),
+			None => {
+				return Err(VerifyError {
+					problem: Problem::IllegalMove {
+						step: i + 1,
+						m,
+						from: from.clone(),
+					},
+					size,
+				})
+			}
+		}
+	}
+	verify_states(&states, size, None)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::map::Map;
+	use crate::state::parse_moves;
+
+	fn state(board: Vec<u16>) -> State {
+		State::from(Map { size: 3, board })
+	}
+
+	#[test]
+	fn moves() {
+		let start = state(vec![1, 2, 3, 8, 6, 4, 7, 5, 0]);
+		assert_eq!(
+			verify_moves(start.clone(), &parse_moves("LU").unwrap(), 3).unwrap(),
+			2
+		);
+		let e = verify_moves(start.clone(), &parse_moves("LUD").unwrap(), 3).unwrap_err();
+		assert!(matches!(e.problem, Problem::Unsolved { .. }));
+		let e = verify_moves(start, &parse_moves("LLLU").unwrap(), 3).unwrap_err();
+		assert!(matches!(
+			e.problem,
+			Problem::IllegalMove {
+				step: 3,
+				m: Move::Left,
+				..
+			}
+		));
+		assert!(e
+			.to_string()
+			.starts_with("Step 3: the blank cannot move Left"));
+	}
+
+	#[test]
+	fn states() {
+		let start = state(vec![1, 2, 3, 8, 6, 4, 7, 5, 0]);
+		let middle = state(vec![1, 2, 3, 8, 6, 4, 7, 0, 5]);
+		let goal = state(gen_solved_map(3).board);
+		let states = vec![start.clone(), middle.clone(), goal.clone()];
+		assert_eq!(verify_states(&states, 3, Some(&start)).unwrap(), 2);
+
+		let e = verify_states(&states, 3, Some(&middle)).unwrap_err();
+		assert!(matches!(e.problem, Problem::WrongStart { .. }));
+		// Skipping a state is two moves at once
+		let e = verify_states(&[start.clone(), goal], 3, None).unwrap_err();
+		assert!(matches!(e.problem, Problem::IllegalStep { step: 1, .. }));
+		let e = verify_states(&[start.clone(), start], 3, None).unwrap_err();
+		assert!(matches!(e.problem, Problem::IllegalStep { step: 1, .. }));
+		// A wrong blank position must not allow swapping two tiles
+		let forged = State::new(vec![1, 2, 3, 8, 0, 4, 7, 6, 5], Point::from_1d(6, 3));
+		let swapped = state(vec![1, 2, 3, 8, 0, 4, 6, 7, 5]);
+		assert!(IntoIterator::into_iter(forged.gen_children(3))
+			.flatten()
+			.any(|child| child == swapped));
+		let e = verify_states(&[forged, swapped], 3, None).unwrap_err();
+		assert!(matches!(e.problem, Problem::IllegalStep { step: 1, .. }));
+		let e = verify_states(&[], 3, None).unwrap_err();
+		assert!(matches!(e.problem, Problem::Empty));
+	}
+}